@@ -0,0 +1,184 @@
+use crate::description::DescriptionComposition;
+use crate::field_filter::FieldFilter;
+use crate::raml::GenerationMode;
+use crate::read_file;
+use crate::system_field::SystemField;
+use anyhow::Result as Rslt;
+use anyhow::bail;
+use std::path::Path;
+
+/// settings shared by every object xraml generates.
+///
+/// read from a file with one `key = value` per line. lists are comma separated, `#` starts a
+/// comment at the start of a line or after whitespace, but not inside a `/.../` pattern
+///
+/// ```text
+/// # types of the library: flat, request_response or crud. see `GenerationMode`
+/// mode = crud
+/// # inject these standard fields into every object. `*` means all of them
+/// system_fields = Id, Name, CreatedDate
+/// # field rules, see `FieldFilter`
+/// exclude_fields = Internal*, /^Legacy.*__c$/
+/// exclude_types = EncryptedText
+/// # regenerate raml keeping hand edits. these facets always keep the value in the library
+/// merge = true
+/// user_facets = description, example
+/// # raml descriptions, see `DescriptionComposition`
+/// description_parts = description, help_text
+/// description_column = 説明
+/// # also write `foo-en_US.raml` with the labels of `objectTranslations`
+/// languages = en_US
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq,)]
+pub struct Config {
+	pub mode:          GenerationMode,
+	/// standard fields injected into generated types. see [`crate::system_field::SYSTEM_FIELDS`].
+	/// object types, and so record types with an `Id`, exist in all modes but `flat`
+	pub system_fields: Vec<String,>,
+	pub filter:        FieldFilter,
+	pub descriptions:  DescriptionComposition,
+	/// merge into existing raml libraries instead of overwriting them
+	pub merge:         bool,
+	/// facets regeneration never touches. see [`crate::raml_merge::merge_raml`]
+	pub user_facets:   Vec<String,>,
+	/// languages a translated library is written for, next to the original one
+	pub languages:     Vec<String,>,
+}
+
+impl Config {
+	pub fn parse(content: &str,) -> Rslt<Self,> {
+		let mut config = Self::default();
+
+		for (i, line,) in content.lines().enumerate() {
+			let line = strip_comment(line,).trim();
+			if line.is_empty() {
+				continue;
+			}
+
+			let Some((key, value,),) = line.split_once('=',) else {
+				bail!("line {}: expect `key = value`, found `{line}`", i + 1)
+			};
+			let value = value.trim();
+
+			let key = key.trim();
+			if !config.set(key, value,)? {
+				bail!("line {}: unknown key `{key}`", i + 1)
+			}
+		}
+
+		Ok(config,)
+	}
+
+	/// applies one setting. command line options go through here too. returns `false` for
+	/// unknown keys
+	pub fn set(&mut self, key: &str, value: &str,) -> Rslt<bool,> {
+		match key {
+			"mode" => self.mode = value.parse()?,
+			"system_fields" => {
+				self.system_fields =
+					if value == "*" { SystemField::all_names() } else { split_list(value,) };
+				for name in &self.system_fields {
+					if SystemField::find(name,).is_none() {
+						bail!("`{name}` is not in the catalogue of system fields")
+					}
+				}
+			},
+			"merge" => self.merge = parse_bool(key, value,)?,
+			"user_facets" => self.user_facets = split_list(value,),
+			"languages" => self.languages = split_list(value,),
+			key if self.descriptions.set(key, value,)? => (),
+			key => return self.filter.set(key, value,),
+		}
+		Ok(true,)
+	}
+
+	pub fn load(path: impl AsRef<Path,>,) -> Rslt<Self,> {
+		let content = read_file(path,)?;
+		Self::parse(&content,)
+	}
+
+	/// same as [`Config::load`], but falls back to the default if `path` does not exist
+	pub fn load_or_default(path: impl AsRef<Path,>,) -> Rslt<Self,> {
+		if path.as_ref().exists() { Self::load(path,) } else { Ok(Self::default(),) }
+	}
+}
+
+/// `line` up to its comment. `#` is part of the value when it follows anything but whitespace,
+/// e.g. `説明#2`, or is inside a `/.../` pattern
+fn strip_comment(line: &str,) -> &str {
+	let mut in_pattern = false;
+	let mut prev = None;
+	for (i, c,) in line.char_indices() {
+		let after_space = prev.is_none_or(char::is_whitespace,);
+		match c {
+			'/' if in_pattern => in_pattern = prev == Some('\\',),
+			'/' => in_pattern = after_space || matches!(prev, Some(',' | '=',)),
+			'#' if !in_pattern && after_space => return &line[..i],
+			_ => (),
+		}
+		prev = Some(c,);
+	}
+	line
+}
+
+pub fn parse_bool(key: &str, value: &str,) -> Rslt<bool,> {
+	match value {
+		"true" => Ok(true,),
+		"false" => Ok(false,),
+		_ => bail!("`{key}` expects true or false, found `{value}`"),
+	}
+}
+
+pub(crate) fn split_list(value: &str,) -> Vec<String,> {
+	value.split(',',).map(|s| s.trim().to_string(),).filter(|s| !s.is_empty(),).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::description::DescriptionPart;
+
+	#[test]
+	fn test_parse_config() -> Rslt<(),> {
+		let config = Config::parse("# standard fields\nsystem_fields = Id, CreatedDate\n\n",)?;
+		assert_eq!(config.system_fields, ["Id", "CreatedDate"]);
+
+		let config = Config::parse("system_fields = *",)?;
+		assert_eq!(config.system_fields.len(), 10);
+
+		let config = Config::parse("exclude_types = EncryptedText\nrequired_only = true",)?;
+		assert_eq!(config.filter.exclude_types, ["EncryptedText"]);
+		assert!(config.filter.required_only);
+
+		let config = Config::parse("merge = true\nuser_facets = description, example",)?;
+		assert!(config.merge);
+		assert_eq!(config.user_facets, ["description", "example"]);
+
+		let config = Config::parse("description_parts = label\ndescription_column = 説明",)?;
+		assert_eq!(config.descriptions.parts, [DescriptionPart::Label]);
+		assert_eq!(config.descriptions.column.as_deref(), Some("説明"));
+
+		let config = Config::parse("mode = request_response",)?;
+		assert_eq!(config.mode, GenerationMode::RequestResponse);
+		assert_eq!(Config::default().mode, GenerationMode::Flat);
+
+		let config = Config::parse("languages = en_US, zh_CN",)?;
+		assert_eq!(config.languages, ["en_US", "zh_CN"]);
+
+		let config = Config::parse("exclude_fields = /^Old#\\d+$/, Tmp* # legacy\n#",)?;
+		let patterns: Vec<_,> = config.filter.exclude_fields.iter().map(|p| p.as_str(),).collect();
+		assert_eq!(patterns, ["^Old#\\d+$", "Tmp*"]);
+		let config = Config::parse("description_column = 説明#2 # column 8",)?;
+		assert_eq!(config.descriptions.column.as_deref(), Some("説明#2"));
+		Ok((),)
+	}
+
+	#[test]
+	fn test_parse_invalid_config() {
+		assert!(Config::parse("system_fields",).is_err());
+		assert!(Config::parse("unknown = 1",).is_err());
+		assert!(Config::parse("system_fields = Foo__c",).is_err());
+		assert!(Config::parse("required_only = yes",).is_err());
+		assert!(Config::parse("mode = nested",).is_err());
+	}
+}
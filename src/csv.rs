@@ -13,6 +13,8 @@ const PROPERTY_FILE_HEADER: &str = "name,example";
 #[derive(Debug,)]
 pub struct Csv {
 	rows:               CsvRows,
+	/// cells of the header, joined over its lines
+	header:             Vec<String,>,
 	pub target_columns: Vec<usize,>,
 }
 
@@ -34,6 +36,34 @@ impl Csv {
 		rslt
 	}
 
+	/// headers of the columns from the first `CSV` column on, where the 〇 markers are
+	pub fn marker_columns(&self,) -> &[String] {
+		self.target_columns.get(1,).map_or(&[], |first| &self.header[*first..],)
+	}
+
+	/// `(api name, cells of the marker columns)` of each row
+	pub fn markers(&self,) -> Vec<(String, Vec<String,>,),> {
+		let [name, first, ..] = self.target_columns[..] else { return vec![] };
+		self.rows
+			.clone()
+			.filter(|v| v.len() > first,)
+			.map(|v| (v[name].clone(), v[first..].to_vec(),),)
+			.collect()
+	}
+
+	/// `(api name, cell)` of the column named `header`. empty cells are skipped
+	pub fn column_values(&self, header: &str,) -> Vec<(String, String,),> {
+		let Some(column,) = self.header.iter().position(|cell| cell.trim() == header,) else {
+			return vec![];
+		};
+		let name = self.target_columns[0];
+		self.rows
+			.clone()
+			.filter(|v| v.len() > column && !v[column].trim().is_empty(),)
+			.map(|v| (v[name].clone(), v[column].trim().to_string(),),)
+			.collect()
+	}
+
 	pub fn update_property_file(self,) -> Rslt<String,> {
 		let content = read_property_file()?;
 		let content = self.update_property_file_content(content,)?;
@@ -98,7 +128,14 @@ impl Iterator for CsvRows {
 }
 
 pub fn read_as_csv(path: impl AsRef<Path,>,) -> Rslt<Csv,> {
-	let contents = read_file(path,)?;
+	let csv = parse_as_csv(&read_file(path,)?,)?;
+	println!("target_columns: {:?}", csv.target_columns);
+	Ok(csv,)
+}
+
+/// reads a spec sheet exported as csv. the header follows the `項目一覧` line and ends with the
+/// line naming the `CSV` column. rows start with an empty cell and a row number
+pub fn parse_as_csv(contents: &str,) -> Rslt<Csv,> {
 	let (_, post,) =
 		contents.split_once("項目一覧",).ok_or(anyhow!("csv file has unexpected format"),)?;
 
@@ -120,17 +157,15 @@ pub fn read_as_csv(path: impl AsRef<Path,>,) -> Rslt<Csv,> {
 		.collect();
 
 	let header_count = header.len();
+	let header: Vec<String,> = header.join("",).split(',',).map(|s| s.to_string(),).collect();
 	let target_columns = header
-		.join("",)
-		.split(',',)
+		.iter()
 		.enumerate()
 		.filter_map(|(i, name,)| {
 			if name.contains("CSV",) || name.contains("API参照名",) { Some(i,) } else { None }
 		},)
 		.collect();
 
-	println!("target_columns: {target_columns:?}");
-
 	let mut data = Vec::with_capacity(400,);
 	let lines =
 		post.lines().skip(header_count,).skip_while(|s| s.split_once(',',).unwrap().0 != "",);
@@ -146,9 +181,58 @@ pub fn read_as_csv(path: impl AsRef<Path,>,) -> Rslt<Csv,> {
 			row.push_str(line,);
 		}
 	}
+	// the row being read when the lines run out is the last one of the sheet
+	data.push(row,);
 	let rows = CsvRows { data, current_row: 0, };
 
-	Ok(Csv { rows, target_columns, },)
+	Ok(Csv { rows, header, target_columns, },)
+}
+
+/// rows of an rfc 4180 csv such as data loader writes. quoted cells may hold commas, line breaks
+/// and quotes doubled as `""`
+pub fn parse_csv_records(content: &str,) -> Rslt<Vec<Vec<String,>,>,> {
+	let content = content.strip_prefix('\u{feff}',).unwrap_or(content,);
+	let mut records = vec![];
+	let mut record = vec![];
+	let mut cell = String::new();
+	let mut in_quotes = false;
+	let mut chars = content.chars().peekable();
+
+	while let Some(c,) = chars.next() {
+		match c {
+			'"' if in_quotes && chars.peek() == Some(&'"',) => {
+				chars.next();
+				cell.push('"',);
+			},
+			'"' if in_quotes => in_quotes = false,
+			'"' if cell.is_empty() => in_quotes = true,
+			_ if in_quotes => cell.push(c,),
+			',' => record.push(std::mem::take(&mut cell,),),
+			'\r' if chars.peek() == Some(&'\n',) => (),
+			'\n' => {
+				record.push(std::mem::take(&mut cell,),);
+				records.push(std::mem::take(&mut record,),);
+			},
+			_ => cell.push(c,),
+		}
+	}
+	if in_quotes {
+		bail!("csv ends inside a quoted cell")
+	}
+	if !cell.is_empty() || !record.is_empty() {
+		record.push(cell,);
+		records.push(record,);
+	}
+	Ok(records,)
+}
+
+/// quotes a cell holding commas, quotes or line breaks
+pub fn csv_cell(text: &str,) -> String {
+	if text.contains([',', '"', '\n', '\r',],) {
+		format!("\"{}\"", text.replace('"', "\"\"",))
+	} else {
+		text.to_string()
+	}
 }
 
 pub fn property_file_line_format(name: impl Display, example: Option<impl Display,>,) -> String {
@@ -158,6 +242,17 @@ pub fn property_file_line_format(name: impl Display, example: Option<impl Displa
 	}
 }
 
+/// `(name, example)` pairs of a property file. rows without example are skipped
+pub fn parse_property_file(content: &str,) -> Vec<(String, String,),> {
+	content
+		.lines()
+		.filter(|line| *line != PROPERTY_FILE_HEADER,)
+		.filter_map(|line| line.split_once(',',),)
+		.filter(|(_, example,)| !example.is_empty(),)
+		.map(|(name, example,)| (name.to_string(), example.to_string(),),)
+		.collect()
+}
+
 pub fn open_property_file(read: bool, write: bool,) -> Rslt<File,> {
 	if !read && !write {
 		bail!("invalid argument. both read/write are false")
@@ -207,12 +302,25 @@ mod tests {
 		let csv = csv_template()?;
 
 		let voids: Vec<Vec<String,>,> = csv.rows.into_iter().collect();
-		assert_eq!(voids.len(), 347, "{voids:#?}");
+		assert_eq!(voids.len(), 348, "{voids:#?}");
 
 		voids.iter().for_each(|s| assert!(!s.is_empty()),);
 		Ok((),)
 	}
 
+	#[test]
+	fn test_parse_as_csv() -> Rslt<(),> {
+		let csv = parse_as_csv(
+			"項目一覧\n,No.,項目名,API参照名,CSV\n,1,数量,Quantity__c,〇\n,2,単価,UnitPrice__c,\n\
+			 ,3,備考,Note__c,〇",
+		)?;
+		let rows: Vec<Vec<String,>,> = csv.rows.clone().collect();
+		assert_eq!(rows.len(), 3);
+		assert_eq!(rows[2], ["", "3", "備考", "Note__c", "〇"]);
+		assert_eq!(csv.acquire_required_rows_name(), ["Quantity__c", "Note__c"]);
+		Ok((),)
+	}
+
 	#[test]
 	fn test_acquire_required() -> Rslt<(),> {
 		let csv = csv_template()?;
@@ -234,6 +342,26 @@ mod tests {
 		Ok((),)
 	}
 
+	#[test]
+	fn test_parse_csv_records() -> Rslt<(),> {
+		let content = "\u{feff}Name,Note__c\r\n\"a,b\",\"say \"\"hi\"\"\nbye\"\r\nc,\n";
+		let records = parse_csv_records(content,)?;
+		assert_eq!(records, [
+			vec!["Name", "Note__c"],
+			vec!["a,b", "say \"hi\"\nbye"],
+			vec!["c", ""],
+		]);
+		assert!(parse_csv_records("\"open",).is_err());
+		Ok((),)
+	}
+
+	#[test]
+	fn test_parse_property_file() {
+		let content = "name,example\nAccessCode__c,AC-001\nQuantity__c,\n";
+		let properties = parse_property_file(content,);
+		assert_eq!(properties, [("AccessCode__c".to_string(), "AC-001".to_string())]);
+	}
+
 	#[test]
 	#[should_panic]
 	fn test_open_pfile_with_invalid_argument() {
@@ -0,0 +1,276 @@
+use crate::csv::csv_cell;
+use crate::csv::parse_csv_records;
+use crate::fake_data::FakeDataGenerator;
+use crate::fake_data::value_cell;
+use crate::payload::PayloadMode;
+use crate::payload::PayloadViolation;
+use crate::payload::validate_payload;
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use anyhow::Result as Rslt;
+use serde_json::Map;
+use serde_json::Value;
+use std::fmt::Display;
+
+/// cell value data loader and bulk api read as "set the field to null"
+const NULL_CELL: &str = "#N/A";
+
+/// a violation found in one row of a data csv
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct CsvRowViolation {
+	/// 1 based like a spreadsheet, so the header is row 1
+	pub row:       usize,
+	pub violation: PayloadViolation,
+}
+
+impl Display for CsvRowViolation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_,>,) -> std::fmt::Result {
+		let PayloadViolation { field, label, error_code, message, .. } = &self.violation;
+		match (field.as_str(), label.as_str(),) {
+			("", _,) => write!(f, "row {}: {message} ({error_code})", self.row),
+			(field, "",) => write!(f, "row {}, {field}: {message} ({error_code})", self.row),
+			(field, label,) => {
+				write!(f, "row {}, {field} ({label}): {message} ({error_code})", self.row)
+			},
+		}
+	}
+}
+
+/// checks a data loader or bulk api csv with api name headers, cell by cell. empty cells leave
+/// the field unset, so they only matter for required fields on create
+pub fn validate_data_csv(
+	data: &RamlMetadataStream,
+	content: &str,
+	mode: PayloadMode,
+) -> Rslt<Vec<CsvRowViolation,>,> {
+	let mut records = parse_csv_records(content,)?.into_iter();
+	let Some(header,) = records.next() else {
+		return Ok(vec![row_violation(1, "", "csv has no header",)],);
+	};
+
+	let mut violations = vec![];
+	let mut columns = vec![];
+	for (i, name,) in header.iter().enumerate() {
+		let name = name.trim();
+		if header[..i].iter().any(|other| other.trim() == name,) {
+			let message = format!("column {name} appears more than once");
+			violations.push(row_violation(1, name, &message,),);
+			continue;
+		}
+		match data.fields().iter().find(|metadata| metadata.name == name,) {
+			Some(metadata,) => columns.push((i, metadata,),),
+			// the record id of update and upsert loads is not part of the object metadata
+			None if name == "Id" => (),
+			None => {
+				let message = format!("no such field on {}", data.object_name());
+				violations.push(row_violation(1, name, &message,),);
+			},
+		}
+	}
+
+	for (i, cells,) in records.enumerate() {
+		let row = i + 2;
+		if cells.len() != header.len() {
+			let message = format!("expect {} cells, found {}", header.len(), cells.len());
+			violations.push(row_violation(row, "", &message,),);
+			continue;
+		}
+
+		let record: Map<String, Value,> = columns
+			.iter()
+			.filter(|(i, _,)| !cells[*i].is_empty(),)
+			.map(|(i, metadata,)| (metadata.name.clone(), cell_value(metadata, &cells[*i],),),)
+			.collect();
+		let found = validate_payload(data, &Value::Object(record,), mode,);
+		violations.extend(found.into_iter().map(|violation| CsvRowViolation { row, violation, },),);
+	}
+	Ok(violations,)
+}
+
+/// typed value of a cell. cells that do not parse stay strings, so the payload check reports them
+fn cell_value(metadata: &RamlTypesMetadata, cell: &str,) -> Value {
+	if cell == NULL_CELL {
+		return Value::Null;
+	}
+	let parsed = match metadata.type_on_raml {
+		RamlType::Number => cell
+			.parse::<i64>()
+			.map(Value::from,)
+			.or_else(|_| cell.parse::<f64>().map(Value::from,),)
+			.ok(),
+		// the spellings data loader accepts for checkboxes
+		RamlType::Boolean => match cell.to_lowercase().as_str() {
+			"true" | "yes" | "y" | "on" | "1" => Some(Value::Bool(true,),),
+			"false" | "no" | "n" | "off" | "0" => Some(Value::Bool(false,),),
+			_ => None,
+		},
+		_ => None,
+	};
+	parsed.unwrap_or_else(|| Value::String(cell.to_string(),),)
+}
+
+fn row_violation(row: usize, field: &str, message: &str,) -> CsvRowViolation {
+	let violation = PayloadViolation {
+		pointer:    String::new(),
+		field:      field.to_string(),
+		label:      String::new(),
+		error_code: "INVALID_FIELD",
+		message:    message.to_string(),
+	};
+	CsvRowViolation { row, violation, }
+}
+
+/// data csv to fill in, with api name headers of the writable fields
+#[derive(Debug, Clone,)]
+pub struct CsvTemplate<'a,> {
+	data:          &'a RamlMetadataStream,
+	required_only: bool,
+	order:         Vec<String,>,
+	label_row:     bool,
+	sample_rows:   usize,
+	seed:          u64,
+}
+
+impl<'a,> CsvTemplate<'a,> {
+	pub fn new(data: &'a RamlMetadataStream,) -> Self {
+		Self {
+			data,
+			required_only: false,
+			order: vec![],
+			label_row: false,
+			sample_rows: 0,
+			seed: 0,
+		}
+	}
+
+	pub fn required_only(mut self,) -> Self {
+		self.required_only = true;
+		self
+	}
+
+	/// columns follow `names`, such as the spec csv rows of
+	/// [`Csv::acquire_required_rows_name`](crate::csv::Csv::acquire_required_rows_name). fields
+	/// not in `names` come last in object order
+	pub fn in_order(mut self, names: Vec<String,>,) -> Self {
+		self.order = names;
+		self
+	}
+
+	/// labels under the header for business users. the row has to be removed before loading
+	pub fn with_label_row(mut self,) -> Self {
+		self.label_row = true;
+		self
+	}
+
+	/// rows of valid fake data instead of an empty template
+	pub fn with_sample_rows(mut self, count: usize, seed: u64,) -> Self {
+		self.sample_rows = count;
+		self.seed = seed;
+		self
+	}
+
+	pub fn columns(&self,) -> Vec<&'a RamlTypesMetadata,> {
+		let mut columns: Vec<_,> = self
+			.data
+			.fields()
+			.iter()
+			.filter(|metadata| !metadata.read_only && (metadata.required || !self.required_only),)
+			.collect();
+		let position = |metadata: &RamlTypesMetadata| {
+			self.order.iter().position(|name| *name == metadata.name,).unwrap_or(usize::MAX,)
+		};
+		columns.sort_by_key(|metadata| position(metadata,),);
+		columns
+	}
+
+	pub fn render(&self,) -> String {
+		let columns = self.columns();
+		let row = |cells: Vec<String,>| cells.join(",",) + "\n";
+
+		let mut csv = row(columns.iter().map(|metadata| metadata.name.clone(),).collect(),);
+		if self.label_row {
+			let label = |metadata: &&RamlTypesMetadata| {
+				csv_cell(metadata.desc.lines().next().unwrap_or_default(),)
+			};
+			csv += &row(columns.iter().map(label,).collect(),);
+		}
+		let records = FakeDataGenerator::new(self.data, self.seed,).records(self.sample_rows,);
+		for record in records {
+			let cells = columns.iter().map(|metadata| record.values.get(&metadata.name,),);
+			csv += &row(cells.map(value_cell,).collect(),);
+		}
+		csv
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_validate_data_csv() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let content = "\
+Agreement__c,Quantity__c,IsSigned__c,StartDate__c,AccessCode__c,Unknown__c
+89：一般,12,yes,2024-04-01,\"AC,001\",x
+99：その他,12345,maybe,2024/04/01,XXXXXXXXXXXXXXXXXXX,x
+89：一般,,#N/A,,,x
+89：一般,1
+";
+		let violations = validate_data_csv(&raml_stream, content, PayloadMode::Create,)?;
+		let found: Vec<_,> = violations
+			.iter()
+			.map(|v| (v.row, v.violation.field.as_str(), v.violation.error_code,),)
+			.collect();
+		assert_eq!(found, [
+			(1, "Unknown__c", "INVALID_FIELD"),
+			(3, "Agreement__c", "INVALID_OR_NULL_FOR_RESTRICTED_PICKLIST"),
+			(3, "Quantity__c", "NUMBER_OUTSIDE_VALID_RANGE"),
+			(3, "IsSigned__c", "JSON_PARSER_ERROR"),
+			(3, "StartDate__c", "JSON_PARSER_ERROR"),
+			(3, "AccessCode__c", "STRING_TOO_LONG"),
+			(4, "Quantity__c", "REQUIRED_FIELD_MISSING"),
+			(5, "", "INVALID_FIELD"),
+		]);
+		assert_eq!(
+			violations[2].to_string(),
+			"row 3, Quantity__c (数量): 12345 has more than 4 integer digits \
+			 (NUMBER_OUTSIDE_VALID_RANGE)"
+		);
+		assert_eq!(violations[7].to_string(), "row 5: expect 6 cells, found 2 (INVALID_FIELD)");
+		Ok((),)
+	}
+
+	#[test]
+	fn test_csv_template() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let template = CsvTemplate::new(&raml_stream,);
+		assert_eq!(
+			template.render(),
+			"AccessCode__c,Agreement__c,Account__c,Quantity__c,UnitPrice__c,IsSigned__c,StartDate__c\n"
+		);
+
+		let order = vec!["Quantity__c".to_string(), "Agreement__c".to_string()];
+		let template = CsvTemplate::new(&raml_stream,).required_only().in_order(order,);
+		let labeled = template.clone().with_label_row().render();
+		assert_eq!(labeled, "Quantity__c,Agreement__c\n数量,36協定区分\n");
+
+		let csv = template.with_sample_rows(20, 3,).render();
+		assert_eq!(csv.lines().count(), 21);
+		assert_eq!(validate_data_csv(&raml_stream, &csv, PayloadMode::Create,)?, []);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_update_load() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let content = "Id,UnitPrice__c,Quantity__c\na00000000000000AAA,10.5,\n";
+		assert_eq!(validate_data_csv(&raml_stream, content, PayloadMode::Update,)?, []);
+		assert_eq!(validate_data_csv(&raml_stream, "", PayloadMode::Update,)?.len(), 1);
+		Ok((),)
+	}
+}
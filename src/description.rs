@@ -0,0 +1,143 @@
+use crate::config::split_list;
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlTypesMetadata;
+use anyhow::Result as Rslt;
+use anyhow::bail;
+use std::str::FromStr;
+
+/// field metadata a raml description can be made of
+#[derive(Debug, Clone, Copy, PartialEq, Eq,)]
+pub enum DescriptionPart {
+	/// `<label>`, which is `displayName` already
+	Label,
+	/// `<description>`
+	Description,
+	/// `<inlineHelpText>`
+	HelpText,
+}
+
+impl FromStr for DescriptionPart {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str,) -> Rslt<Self,> {
+		match s {
+			"label" => Ok(Self::Label,),
+			"description" => Ok(Self::Description,),
+			"help_text" => Ok(Self::HelpText,),
+			_ => bail!("unknown description part `{s}`. expect label, description or help_text"),
+		}
+	}
+}
+
+/// how the `description` facet of field types is put together
+///
+/// ```text
+/// # parts joined by a blank line. the label is used when all of them are empty
+/// description_parts = description, help_text
+/// # spec csv column whose cells replace the composed description
+/// description_column = 説明
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct DescriptionComposition {
+	pub parts:  Vec<DescriptionPart,>,
+	/// header of the spec csv column overriding descriptions. `None` reads no column
+	pub column: Option<String,>,
+}
+
+impl Default for DescriptionComposition {
+	fn default() -> Self {
+		Self { parts: vec![DescriptionPart::Description, DescriptionPart::HelpText], column: None, }
+	}
+}
+
+impl DescriptionComposition {
+	/// same contract as [`crate::config::Config::set`]
+	pub fn set(&mut self, key: &str, value: &str,) -> Rslt<bool,> {
+		match key {
+			"description_parts" => {
+				self.parts = split_list(value,).iter().map(|part| part.parse(),).try_collect()?;
+			},
+			"description_column" => self.column = Some(value.to_string(),),
+			_ => return Ok(false,),
+		}
+		Ok(true,)
+	}
+
+	pub fn compose(&self, metadata: &RamlTypesMetadata,) -> String {
+		let parts: Vec<&str,> = self
+			.parts
+			.iter()
+			.filter_map(|part| match part {
+				DescriptionPart::Label => Some(metadata.desc.as_str(),),
+				DescriptionPart::Description => metadata.description.as_deref(),
+				DescriptionPart::HelpText => metadata.help_text.as_deref(),
+			},)
+			.map(str::trim,)
+			.filter(|part| !part.is_empty(),)
+			.collect();
+		if parts.is_empty() { metadata.desc.clone() } else { parts.join("\n\n",) }
+	}
+}
+
+impl RamlMetadataStream {
+	/// sets the description raml gets for every field. `overrides` are `(field name, text)`
+	/// pairs, such as the cells of the spec csv column, and win over the composition
+	pub fn compose_descriptions(
+		mut self,
+		composition: &DescriptionComposition,
+		overrides: &[(String, String,)],
+	) -> Self {
+		for metadata in self.fields_mut() {
+			let overridden = overrides.iter().find(|(name, _,)| *name == metadata.name,);
+			let description = match overridden {
+				Some((_, text,),) if !text.is_empty() => text.clone(),
+				_ => composition.compose(metadata,),
+			};
+			metadata.raml_description = Some(description,);
+		}
+		self
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+	use crate::raml::render_raml;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	fn descriptions(stream: &RamlMetadataStream,) -> Vec<Option<&str,>,> {
+		stream.fields()[..2].iter().map(|metadata| metadata.raml_description.as_deref(),).collect()
+	}
+
+	#[test]
+	fn test_compose_descriptions() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let composed = raml_stream.clone().compose_descriptions(&Default::default(), &[],);
+		assert_eq!(descriptions(&composed,), [
+			Some(
+				"電子契約サービスから払い出される | 区切りなしの照会コード\n\n契約書の URL に含まれる \
+				 18 桁の英数字"
+			),
+			Some("36協定区分"),
+		]);
+
+		let mut composition = DescriptionComposition::default();
+		assert!(composition.set("description_parts", "help_text, label",)?);
+		assert!(!composition.set("exclude_types", "Text",)?);
+		let overrides = vec![("Agreement__c".to_string(), "労使協定の区分".to_string())];
+		let composed = raml_stream.compose_descriptions(&composition, &overrides,);
+		assert_eq!(descriptions(&composed,), [
+			Some("契約書の URL に含まれる 18 桁の英数字\n\n電子契約-アクセスコード"),
+			Some("労使協定の区分"),
+		]);
+
+		let rendered = render_raml(&composed, Default::default(),)?;
+		let expected = "  Agreement__c:\n    type: string\n    displayName: \"36協定区分\"\n    \
+		                description: |\n      労使協定の区分\n";
+		assert!(rendered.contains(expected), "{rendered}");
+		assert!(composition.set("description_parts", "summary",).is_err());
+		Ok((),)
+	}
+}
@@ -0,0 +1,239 @@
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlTypesMetadata;
+use anyhow::Result as Rslt;
+use anyhow::bail;
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default,)]
+pub enum DiagramFormat {
+	/// `erDiagram` block for markdown viewers
+	#[default]
+	Mermaid,
+	/// graphviz source
+	Dot,
+}
+
+impl FromStr for DiagramFormat {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str,) -> Rslt<Self,> {
+		match s {
+			"mermaid" => Ok(Self::Mermaid,),
+			"dot" => Ok(Self::Dot,),
+			_ => bail!("unknown format `{s}`. expect mermaid or dot"),
+		}
+	}
+}
+
+/// a lookup or master-detail field, one per target of polymorphic lookups
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct Relationship {
+	/// object holding the field
+	pub child:     String,
+	pub field:     String,
+	pub parent:    String,
+	/// master-detail fields and required lookups. a child always has a parent then
+	pub mandatory: bool,
+}
+
+/// relationships of every field of `objects`, in field order. parents outside of `objects` are
+/// included
+pub fn relationships(objects: &[RamlMetadataStream],) -> Vec<Relationship,> {
+	objects
+		.iter()
+		.flat_map(|data| {
+			data.fields().iter().flat_map(move |metadata| {
+				metadata.reference_to.iter().map(move |parent| Relationship {
+					child:     data.object_name().to_string(),
+					field:     metadata.name.clone(),
+					parent:    parent.clone(),
+					mandatory: metadata.required || metadata.sf_type == "MasterDetail",
+				},)
+			},)
+		},)
+		.collect()
+}
+
+pub fn create_er_diagram_file(
+	objects: &[RamlMetadataStream],
+	filename: impl AsRef<Path,>,
+	format: DiagramFormat,
+) -> Rslt<(),> {
+	std::fs::write(filename, render_er_diagram(objects, format,),)?;
+	Ok((),)
+}
+
+/// objects with their key fields, `Id`, lookups and required fields, and the relationships
+/// between them. filter the streams beforehand to draw fewer fields
+pub fn render_er_diagram(objects: &[RamlMetadataStream], format: DiagramFormat,) -> String {
+	match format {
+		DiagramFormat::Mermaid => render_mermaid(objects,),
+		DiagramFormat::Dot => render_dot(objects,),
+	}
+}
+
+/// fields worth drawing, with `PK` or `FK`. `Id` comes first, whether the stream has it or not
+fn key_fields(data: &RamlMetadataStream,) -> Vec<(&str, &str, Option<&'static str,>,),> {
+	let fields = data.fields().iter().filter(|metadata| metadata.name != "Id",);
+	let is_key =
+		|metadata: &&RamlTypesMetadata| !metadata.reference_to.is_empty() || metadata.required;
+	std::iter::once(("Id", "Id", Some("PK",),),)
+		.chain(fields.filter(is_key,).map(|metadata| {
+			let key = if metadata.reference_to.is_empty() { None } else { Some("FK",) };
+			(metadata.name.as_str(), metadata.sf_type.as_str(), key,)
+		},),)
+		.collect()
+}
+
+/// parents that are not part of `objects`, in order of appearance
+fn outside_parents<'a,>(
+	objects: &[RamlMetadataStream],
+	relationships: &'a [Relationship],
+) -> Vec<&'a str,> {
+	let mut parents: Vec<&str,> = vec![];
+	for relationship in relationships {
+		let parent = relationship.parent.as_str();
+		let is_outside = !objects.iter().any(|data| data.object_name() == parent,);
+		if is_outside && !parents.contains(&parent,) {
+			parents.push(parent,);
+		}
+	}
+	parents
+}
+
+fn render_mermaid(objects: &[RamlMetadataStream],) -> String {
+	let mut lines = vec!["erDiagram".to_string()];
+	for data in objects {
+		lines.push(format!("    {} {{", data.object_name()),);
+		for (name, sf_type, key,) in key_fields(data,) {
+			let key = key.map(|key| format!(" {key}"),).unwrap_or_default();
+			lines.push(format!("        {sf_type} {name}{key}"),);
+		}
+		lines.push("    }".to_string(),);
+	}
+	for relationship in relationships(objects,) {
+		let Relationship { child, field, parent, mandatory, } = relationship;
+		let parent_side = if mandatory { "||" } else { "|o" };
+		lines.push(format!("    {parent} {parent_side}--o{{ {child} : \"{field}\""),);
+	}
+	lines.join("\n",) + "\n"
+}
+
+fn render_dot(objects: &[RamlMetadataStream],) -> String {
+	let relationships = relationships(objects,);
+	let mut lines = vec![
+		"digraph er {".to_string(),
+		"    rankdir=LR;".to_string(),
+		"    node [shape=record];".to_string(),
+	];
+	for data in objects {
+		let fields: String = key_fields(data,)
+			.into_iter()
+			.map(|(name, sf_type, key,)| match key {
+				Some(key,) => format!("{name}: {sf_type} ({key})\\l"),
+				None => format!("{name}: {sf_type}\\l"),
+			},)
+			.collect();
+		let object_name = data.object_name();
+		let label = format!("{{{}|{}}}", escape_record(object_name,), escape_record(&fields,));
+		lines.push(format!("    \"{object_name}\" [label=\"{label}\"];"),);
+	}
+	for parent in outside_parents(objects, &relationships,) {
+		lines.push(format!("    \"{parent}\" [shape=box];"),);
+	}
+	for Relationship { child, field, parent, mandatory, } in &relationships {
+		let head = if *mandatory { "1" } else { "0..1" };
+		lines.push(format!(
+			"    \"{child}\" -> \"{parent}\" [label=\"{field}\", taillabel=\"0..*\", \
+			 headlabel=\"{head}\"];"
+		),);
+	}
+	lines.push("}".to_string(),);
+	lines.join("\n",) + "\n"
+}
+
+/// escapes the characters record labels give a meaning. `\l` line ends are kept
+fn escape_record(text: &str,) -> String {
+	text.chars().fold(String::new(), |mut escaped, c| {
+		if matches!(c, '{' | '}' | '|' | '<' | '>' | '"') {
+			escaped.push('\\',);
+		}
+		escaped.push(c,);
+		escaped
+	},)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+	const CONTRACT_PATH: &str = "tests/fixtures/Contract__c.object";
+
+	fn objects() -> Rslt<Vec<RamlMetadataStream,>,> {
+		let sample = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		Ok(vec![sample, create_raml_metadata_stream(CONTRACT_PATH,)?],)
+	}
+
+	#[test]
+	fn test_relationships() -> Rslt<(),> {
+		let found: Vec<_,> = relationships(&objects()?,)
+			.into_iter()
+			.map(|r| (r.child, r.field, r.parent, r.mandatory,),)
+			.collect();
+		let relationship = |child: &str, field: &str, parent: &str, mandatory| {
+			(child.to_string(), field.to_string(), parent.to_string(), mandatory,)
+		};
+		assert_eq!(found, [
+			relationship("Sample__c", "Account__c", "Account", false),
+			relationship("Contract__c", "Sample__c", "Sample__c", true),
+			relationship("Contract__c", "Signer__c", "Contact", false),
+		]);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_render_mermaid() -> Rslt<(),> {
+		let mermaid = render_er_diagram(&objects()?, DiagramFormat::Mermaid,);
+		let expected = "\
+erDiagram
+    Sample__c {
+        Id Id PK
+        Picklist Agreement__c
+        Lookup Account__c FK
+        Number Quantity__c
+    }
+    Contract__c {
+        Id Id PK
+        MasterDetail Sample__c FK
+        Lookup Signer__c FK
+        Number Amount__c
+    }
+    Account |o--o{ Sample__c : \"Account__c\"
+    Sample__c ||--o{ Contract__c : \"Sample__c\"
+    Contact |o--o{ Contract__c : \"Signer__c\"
+";
+		assert_eq!(mermaid, expected);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_render_dot() -> Rslt<(),> {
+		let objects = objects()?;
+		// as if the spec csv only selected the master-detail field
+		let contract = objects[1].clone().filter(|metadata| metadata.name == "Sample__c",);
+		let dot = render_er_diagram(&[objects[0].clone(), contract], DiagramFormat::Dot,);
+		let lines: Vec<_,> = dot.lines().collect();
+		assert_eq!(lines[0], "digraph er {");
+		let contract = r#"[label="{Contract__c|Id: Id (PK)\lSample__c: MasterDetail (FK)\l}"];"#;
+		assert_eq!(lines[4], format!(r#"    "Contract__c" {contract}"#));
+		assert_eq!(lines[5], r#"    "Account" [shape=box];"#);
+		let edge = r#"[label="Sample__c", taillabel="0..*", headlabel="1"];"#;
+		assert_eq!(lines[7], format!(r#"    "Contract__c" -> "Sample__c" {edge}"#));
+		assert!(!dot.contains("Contact"));
+		assert_eq!(escape_record("a|{b}",), r"a\|\{b\}");
+		Ok((),)
+	}
+}
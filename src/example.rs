@@ -0,0 +1,199 @@
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use crate::raml_parser::unquote;
+use anyhow::Result as Rslt;
+use anyhow::anyhow;
+use anyhow::bail;
+use regex::Regex;
+use std::sync::LazyLock;
+
+pub const DATE_EXAMPLE: &str = "2024-04-01";
+pub const DATETIME_EXAMPLE: &str = "2024-04-01T09:00:00Z";
+/// placeholder of text fields, shortened to fit their max length
+const TEXT_EXAMPLE: &str = "XXX";
+
+static DATE: LazyLock<Regex,> =
+	LazyLock::new(|| Regex::new(r"^\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])$",).unwrap(),);
+static DATETIME: LazyLock<Regex,> = LazyLock::new(|| {
+	Regex::new(concat!(
+		r"^\d{4}-(0[1-9]|1[0-2])-(0[1-9]|[12]\d|3[01])",
+		r"T([01]\d|2[0-3]):[0-5]\d:[0-5]\d(\.\d+)?(Z|[+-]\d{2}:\d{2})$",
+	),)
+	.unwrap()
+},);
+
+impl RamlMetadataStream {
+	/// checks every example against its own type. the error lists all invalid examples
+	pub fn validate_examples(&self,) -> Rslt<(),> {
+		let errors: Vec<String,> = self
+			.fields()
+			.iter()
+			.filter_map(|metadata| validate_example(metadata,).err(),)
+			.map(|e| e.to_string(),)
+			.collect();
+		if !errors.is_empty() {
+			bail!("invalid examples in {}:\n{}", self.object_name(), errors.join("\n",))
+		}
+		Ok((),)
+	}
+}
+
+/// raml scalar conforming to `raml_type` and `max_length`. enums take their first value
+pub fn default_example(raml_type: &RamlType, max_length: Option<usize,>,) -> String {
+	match raml_type {
+		RamlType::String | RamlType::Any => {
+			let len = max_length.map_or(TEXT_EXAMPLE.len(), |l| l.min(TEXT_EXAMPLE.len(),),);
+			quote(&TEXT_EXAMPLE[..len],)
+		},
+		RamlType::Number => "0".to_string(),
+		RamlType::Enum(values, base,) => match values.first() {
+			Some(value,) => quote(value,),
+			None => default_example(base, max_length,),
+		},
+		RamlType::Boolean => "true".to_string(),
+		RamlType::Date => quote(DATE_EXAMPLE,),
+		RamlType::DateTime => quote(DATETIME_EXAMPLE,),
+	}
+}
+
+/// checks the example of `metadata` against its type, max length and enum values
+pub fn validate_example(metadata: &RamlTypesMetadata,) -> Rslt<(),> {
+	let name = &metadata.name;
+	let example = metadata.example.trim();
+	if example.is_empty() {
+		bail!("{name}: example is missing")
+	}
+
+	let is_quoted = example.starts_with(['"', '\'',],);
+	let value = unquote(example,);
+	check_type(&metadata.type_on_raml, &value, is_quoted,)
+		.map_err(|e| anyhow!("{name}: example {example} {e}"),)?;
+
+	if let Some(max_length,) = metadata.max_length
+		&& value.chars().count() > max_length
+	{
+		bail!("{name}: example {example} is longer than maxLength {max_length}")
+	}
+	Ok((),)
+}
+
+/// `value` is an unquoted scalar. `is_quoted` tells whether it was written as a string
+pub(crate) fn check_type(raml_type: &RamlType, value: &str, is_quoted: bool,) -> Rslt<(),> {
+	let is_plain = |s: &str| !is_quoted && value == s;
+	match raml_type {
+		RamlType::String => {
+			if is_plain("true",) || is_plain("false",) || !is_quoted && value.parse::<f64>().is_ok() {
+				bail!("is not a string")
+			}
+		},
+		RamlType::Number => {
+			if is_quoted || !value.parse::<f64>().is_ok_and(f64::is_finite,) {
+				bail!("is not a number")
+			}
+		},
+		RamlType::Enum(values, base,) => {
+			if !values.is_empty() && !values.iter().any(|v| v == value,) {
+				bail!("is not one of the enum values")
+			}
+			check_type(base, value, is_quoted,)?;
+		},
+		RamlType::Boolean => {
+			if !is_plain("true",) && !is_plain("false",) {
+				bail!("is not a boolean")
+			}
+		},
+		RamlType::Date => {
+			if !DATE.is_match(value,) {
+				bail!("is not a date in YYYY-MM-DD format")
+			}
+		},
+		RamlType::DateTime => {
+			if !DATETIME.is_match(value,) {
+				bail!("is not an RFC 3339 datetime")
+			}
+		},
+		RamlType::Any => (),
+	}
+	Ok((),)
+}
+
+/// json string, which is also a double quoted yaml scalar
+pub(crate) fn quote(value: &str,) -> String {
+	serde_json::Value::from(value,).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	fn metadata(
+		type_on_raml: RamlType,
+		example: &str,
+		max_length: Option<usize,>,
+	) -> RamlTypesMetadata {
+		RamlTypesMetadata {
+			name: "Field__c".to_string(),
+			sf_type: "Text".to_string(),
+			type_on_raml,
+			desc: "項目".to_string(),
+			description: None,
+			help_text: None,
+			translations: vec![],
+			raml_description: None,
+			example: example.to_string(),
+			default_value: None,
+			max_length,
+			precision: None,
+			scale: None,
+			reference_to: vec![],
+			required: false,
+			read_only: false,
+		}
+	}
+
+	#[test]
+	fn test_generated_examples_are_valid() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?
+			.with_system_fields(&crate::system_field::SystemField::all_names(),)?;
+		raml_stream.validate_examples()?;
+
+		let start_date = raml_stream.fields().iter().find(|f| f.name == "StartDate__c",).unwrap();
+		assert_eq!(start_date.example, "\"2024-04-01\"");
+		Ok((),)
+	}
+
+	#[test]
+	fn test_default_example() {
+		assert_eq!(default_example(&RamlType::String, Some(2,),), "\"XX\"");
+		let empty_enum = RamlType::Enum(vec![], Box::new(RamlType::String,),);
+		assert_eq!(default_example(&empty_enum, None,), "\"XXX\"");
+		assert_eq!(default_example(&RamlType::DateTime, None,), "\"2024-04-01T09:00:00Z\"");
+	}
+
+	#[test]
+	fn test_invalid_examples() {
+		let enum_type = RamlType::Enum(vec!["89：一般".to_string()], Box::new(RamlType::String,),);
+		let cases = [
+			(metadata(RamlType::String, "\"XXXX\"", Some(3,),), "longer than maxLength 3"),
+			(metadata(RamlType::String, "0", None,), "is not a string"),
+			(metadata(RamlType::Number, "\"0\"", None,), "is not a number"),
+			(metadata(RamlType::Number, "1e999", None,), "is not a number"),
+			(metadata(RamlType::Boolean, "yes", None,), "is not a boolean"),
+			(metadata(RamlType::Date, "\"2024-13-01\"", None,), "YYYY-MM-DD"),
+			(metadata(RamlType::DateTime, "\"2024-04-01 09:00\"", None,), "RFC 3339"),
+			(metadata(enum_type, "\"99：その他\"", None,), "not one of the enum values"),
+			(metadata(RamlType::Date, "", None,), "example is missing"),
+		];
+		for (metadata, message,) in cases {
+			let e = validate_example(&metadata,).unwrap_err().to_string();
+			assert!(e.contains(message), "{e}");
+		}
+
+		assert!(validate_example(&metadata(RamlType::String, "'it''s'", None,),).is_ok());
+		assert!(validate_example(&metadata(RamlType::Number, "12.5", None,),).is_ok());
+	}
+}
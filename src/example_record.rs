@@ -0,0 +1,264 @@
+use crate::example::DATE_EXAMPLE;
+use crate::example::quote;
+use crate::example::validate_example;
+use crate::json_schema::parse_example;
+use crate::raml::GenerationMode;
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use crate::raml::render_raml;
+use crate::raml::type_base_name;
+use crate::raml_parser::Yaml;
+use crate::raml_parser::parse_raml_library;
+use crate::rng::Rng;
+use anyhow::Result as Rslt;
+use anyhow::anyhow;
+use anyhow::bail;
+use serde_json::Map;
+use serde_json::Value;
+use std::path::Path;
+
+/// length of random text when the field has no max length
+const TEXT_LEN: usize = 8;
+
+/// sample request bodies of an object. read-only fields are never part of them
+#[derive(Debug, Clone, PartialEq,)]
+pub struct ExampleRecords {
+	/// required fields only
+	pub minimal: Map<String, Value,>,
+	/// every writable field
+	pub full:    Map<String, Value,>,
+}
+
+impl RamlMetadataStream {
+	pub fn create_example_json_file(
+		self,
+		filename: String,
+		properties: &[(String, String,)],
+		seed: u64,
+	) -> Rslt<(),> {
+		let examples = ExampleRecords::new(&self, properties, &mut Rng::new(seed,),)?;
+		std::fs::write(format!("data/{filename}"), examples.to_json_string()?,)?;
+		Ok((),)
+	}
+}
+
+impl ExampleRecords {
+	/// `properties` are `(field name, example)` pairs of the property file. they win over
+	/// random values
+	pub fn new(
+		data: &RamlMetadataStream,
+		properties: &[(String, String,)],
+		rng: &mut Rng,
+	) -> Rslt<Self,> {
+		let mut minimal = Map::new();
+		let mut full = Map::new();
+
+		for metadata in data.fields().iter().filter(|metadata| !metadata.read_only,) {
+			let property = properties.iter().find(|(name, _,)| *name == metadata.name,);
+			let value = match property {
+				Some((_, example,),) => property_value(metadata, example,)?,
+				None => random_value(metadata, rng,),
+			};
+			if metadata.required {
+				minimal.insert(metadata.name.clone(), value.clone(),);
+			}
+			full.insert(metadata.name.clone(), value,);
+		}
+
+		Ok(Self { minimal, full, },)
+	}
+
+	pub fn variants(&self,) -> [(&'static str, &Map<String, Value,>,); 2] {
+		[("minimal", &self.minimal,), ("full", &self.full,)]
+	}
+
+	/// `{"minimal": {...}, "full": {...}}`
+	pub fn to_json(&self,) -> Value {
+		let variants = self.variants();
+		let variants = variants.map(|(name, record,)| (name.to_string(), record.clone().into(),),);
+		Value::Object(variants.into_iter().collect(),)
+	}
+
+	pub fn to_json_string(&self,) -> Rslt<String,> {
+		Ok(serde_json::to_string_pretty(&self.to_json(),)? + "\n",)
+	}
+
+	/// raml `examples` facet with one named example per variant
+	pub fn to_yaml(&self,) -> Yaml {
+		let variants = self.variants().map(|(name, record,)| {
+			let value = record
+				.iter()
+				.map(|(field, value,)| (field.clone(), Yaml::Scalar(value.to_string(),),),)
+				.collect();
+			(name.to_string(), Yaml::Map(vec![("value".to_string(), Yaml::Map(value,),)],),)
+		},);
+		Yaml::Map(variants.into_iter().collect(),)
+	}
+}
+
+/// library of [`render_raml`] with `examples` on the request type, `FooRequest` or `FooCreate`
+pub fn render_raml_with_examples(
+	data: &RamlMetadataStream,
+	mode: GenerationMode,
+	examples: &ExampleRecords,
+) -> Rslt<String,> {
+	let base = type_base_name(data.object_name(),)?;
+	let target = match mode {
+		GenerationMode::Flat => bail!("examples belong to object types, which flat mode has none"),
+		GenerationMode::RequestResponse => format!("{base}Request"),
+		GenerationMode::Crud => format!("{base}Create"),
+	};
+
+	let mut library = parse_raml_library(&render_raml(data, mode,)?,)?;
+	let declaration = library.types.iter_mut().find(|(name, _,)| *name == target,);
+	let Some((_, Yaml::Map(facets,),),) = declaration else {
+		bail!("type `{target}` is not generated")
+	};
+	facets.push(("examples".to_string(), examples.to_yaml(),),);
+	Ok(library.to_raml(),)
+}
+
+pub fn create_raml_file_with_examples(
+	data: &RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	mode: GenerationMode,
+	examples: &ExampleRecords,
+) -> Rslt<(),> {
+	let contents = render_raml_with_examples(data, mode, examples,)?;
+	std::fs::write(filename, contents,)?;
+	Ok((),)
+}
+
+/// property file examples are written without quotes. checked like generated examples
+fn property_value(metadata: &RamlTypesMetadata, example: &str,) -> Rslt<Value,> {
+	let example = match metadata.type_on_raml {
+		RamlType::Number | RamlType::Boolean => example.to_string(),
+		_ => quote(example,),
+	};
+	validate_example(&RamlTypesMetadata { example: example.clone(), ..metadata.clone() },)
+		.map_err(|e| anyhow!("property file: {e}"),)?;
+	let name = &metadata.name;
+	parse_example(&example,).ok_or(anyhow!("property file: {name}: cannot read {example}"),)
+}
+
+/// random value of the field type within its max length and enum values
+pub fn random_value(metadata: &RamlTypesMetadata, rng: &mut Rng,) -> Value {
+	random_value_of(&metadata.type_on_raml, metadata, rng,)
+}
+
+fn random_value_of(raml_type: &RamlType, metadata: &RamlTypesMetadata, rng: &mut Rng,) -> Value {
+	match raml_type {
+		RamlType::String => Value::String(random_text(metadata, rng,),),
+		RamlType::Number => random_number(metadata, rng,),
+		RamlType::Enum(values, base,) => match rng.pick(values,) {
+			Some(value,) => Value::String(value.clone(),),
+			None => random_value_of(base, metadata, rng,),
+		},
+		RamlType::Boolean => Value::Bool(rng.chance(0.5,),),
+		RamlType::Date => Value::String(random_date(rng,),),
+		RamlType::DateTime => {
+			let date = random_date(rng,);
+			let (h, m, s,) = (rng.range(0..=23,), rng.range(0..=59,), rng.range(0..=59,),);
+			Value::String(format!("{date}T{h:02}:{m:02}:{s:02}Z"),)
+		},
+		RamlType::Any => Value::Null,
+	}
+}
+
+fn random_text(metadata: &RamlTypesMetadata, rng: &mut Rng,) -> String {
+	let text = match metadata.sf_type.as_str() {
+		// record ids are 18 characters, starting with a 3 character key prefix
+		"Lookup" | "MasterDetail" | "Id" => format!("a00{}", rng.alphanumeric(15,)),
+		"Email" => format!("user{}@example.com", rng.range(0..=9999,)),
+		"Phone" => format!("03-{:04}-{:04}", rng.range(0..=9999,), rng.range(0..=9999,)),
+		"Url" => format!("https://example.com/{}", rng.alphanumeric(TEXT_LEN,)),
+		_ => rng.alphanumeric(metadata.max_length.unwrap_or(TEXT_LEN,).clamp(1, TEXT_LEN,),),
+	};
+	match metadata.max_length {
+		Some(max_length,) => text.chars().take(max_length,).collect(),
+		None => text,
+	}
+}
+
+/// within the digits `precision` and `scale` allow. integer digits are capped so values stay
+/// exact as f64
+fn random_number(metadata: &RamlTypesMetadata, rng: &mut Rng,) -> Value {
+	let scale = metadata.scale.unwrap_or_default();
+	let integer_digits = metadata.precision.map_or(3, |p| p.saturating_sub(scale,),);
+	let integer = rng.range(0..=10i64.pow(integer_digits.min(9,) as u32,) - 1,);
+	let scale = scale.min(6,);
+	if scale == 0 {
+		return Value::from(integer,);
+	}
+
+	let fraction = rng.range(0..=10i64.pow(scale as u32,) - 1,);
+	let number: f64 = format!("{integer}.{fraction:0scale$}").parse().unwrap();
+	Value::from(number,)
+}
+
+/// within the year of [`DATE_EXAMPLE`]. days stop at 28 to be valid in every month
+fn random_date(rng: &mut Rng,) -> String {
+	let year = &DATE_EXAMPLE[..4];
+	format!("{year}-{:02}-{:02}", rng.range(1..=12,), rng.range(1..=28,))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::json_schema::object_schema;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_example_records() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let properties = vec![("AccessCode__c".to_string(), "AC-001".to_string())];
+		let examples = ExampleRecords::new(&raml_stream, &properties, &mut Rng::new(1,),)?;
+
+		let minimal: Vec<_,> = examples.minimal.keys().collect();
+		assert_eq!(minimal, ["Agreement__c", "Quantity__c"]);
+		assert_eq!(examples.full.len(), 7);
+		assert_eq!(examples.full["AccessCode__c"], "AC-001");
+		assert!(!examples.full.contains_key("TotalAmount__c"));
+
+		let again = ExampleRecords::new(&raml_stream, &properties, &mut Rng::new(1,),)?;
+		assert_eq!(again, examples);
+
+		let validator = jsonschema::draft202012::new(&object_schema(&raml_stream,),)?;
+		for (name, record,) in examples.variants() {
+			let record = Value::Object(record.clone(),);
+			assert!(validator.is_valid(&record), "{name}: {record}");
+		}
+		Ok((),)
+	}
+
+	#[test]
+	fn test_invalid_property_example() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let properties = vec![("Quantity__c".to_string(), "many".to_string())];
+		let e = ExampleRecords::new(&raml_stream, &properties, &mut Rng::new(1,),).unwrap_err();
+		assert_eq!(e.to_string(), "property file: Quantity__c: example many is not a number");
+		Ok((),)
+	}
+
+	#[test]
+	fn test_render_raml_with_examples() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let examples = ExampleRecords::new(&raml_stream, &[], &mut Rng::new(1,),)?;
+		let rendered = render_raml_with_examples(&raml_stream, GenerationMode::Crud, &examples,)?;
+
+		let library = parse_raml_library(&rendered,)?;
+		let create = library.get("SampleCreate",).unwrap();
+		let quantity = &examples.minimal["Quantity__c"];
+		let value = create.get("examples",).and_then(|e| e.get("minimal",)?.get("value",),);
+		let value = value.unwrap();
+		assert_eq!(value.get("Quantity__c").unwrap().as_raw().unwrap(), quantity.to_string());
+		let plain = parse_raml_library(&render_raml(&raml_stream, GenerationMode::Crud,)?,)?;
+		assert_eq!(library.to_metadata_stream()?.fields(), plain.to_metadata_stream()?.fields());
+
+		assert!(render_raml_with_examples(&raml_stream, GenerationMode::Flat, &examples,).is_err());
+		Ok((),)
+	}
+}
@@ -0,0 +1,285 @@
+use crate::csv::csv_cell;
+use crate::example_record::random_value;
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use crate::rng::Rng;
+use anyhow::Result as Rslt;
+use anyhow::bail;
+use serde_json::Map;
+use serde_json::Value;
+use std::path::Path;
+use std::str::FromStr;
+
+/// chance of an optional field to be filled
+const OPTIONAL_FILL_RATE: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default,)]
+pub enum FakeDataFormat {
+	/// one json object per line
+	#[default]
+	JsonLines,
+	/// csv for bulk api 2.0 ingest jobs
+	BulkCsv,
+}
+
+impl FromStr for FakeDataFormat {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str,) -> Rslt<Self,> {
+		match s {
+			"jsonl" => Ok(Self::JsonLines,),
+			"csv" => Ok(Self::BulkCsv,),
+			_ => bail!("unknown format `{s}`. expect jsonl or csv"),
+		}
+	}
+}
+
+#[derive(Debug, Clone, PartialEq,)]
+pub struct FakeRecord {
+	pub values:    Map<String, Value,>,
+	/// what makes the record invalid. `None` for valid records
+	pub violation: Option<Violation,>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct Violation {
+	pub field:   String,
+	pub message: String,
+}
+
+/// records for load and integration tests. valid ones fill required fields and keep maxLength,
+/// picklist values and number precision
+#[derive(Debug, Clone,)]
+pub struct FakeDataGenerator<'a,> {
+	data:    &'a RamlMetadataStream,
+	rng:     Rng,
+	invalid: bool,
+}
+
+impl<'a,> FakeDataGenerator<'a,> {
+	pub fn new(data: &'a RamlMetadataStream, seed: u64,) -> Self {
+		Self { data, rng: Rng::new(seed,), invalid: false, }
+	}
+
+	/// every record breaks exactly one constraint, for negative tests
+	pub fn with_invalid_records(mut self,) -> Self {
+		self.invalid = true;
+		self
+	}
+
+	pub fn records(&mut self, count: usize,) -> Vec<FakeRecord,> {
+		(0..count).map(|_| self.record(),).collect()
+	}
+
+	pub fn record(&mut self,) -> FakeRecord {
+		let mut values = Map::new();
+		for metadata in self.data.fields().iter().filter(|metadata| !metadata.read_only,) {
+			if metadata.required || self.rng.chance(OPTIONAL_FILL_RATE,) {
+				values.insert(metadata.name.clone(), random_value(metadata, &mut self.rng,),);
+			}
+		}
+
+		let violation = if self.invalid { self.break_record(&mut values,) } else { None };
+		FakeRecord { values, violation, }
+	}
+
+	/// `None` if the object has no constraint to break
+	fn break_record(&mut self, values: &mut Map<String, Value,>,) -> Option<Violation,> {
+		let candidates: Vec<_,> =
+			self.data.fields().iter().flat_map(|metadata| violations(metadata,),).collect();
+		let (metadata, kind,) = self.rng.pick(&candidates,)?;
+
+		let name = metadata.name.clone();
+		let message = match kind {
+			ViolationKind::MissingRequired => {
+				values.remove(&name,);
+				"required field is missing".to_string()
+			},
+			ViolationKind::ReadOnly => {
+				values.insert(name.clone(), random_value(metadata, &mut self.rng,),);
+				"read-only field is set".to_string()
+			},
+			ViolationKind::TooLong(max_length,) => {
+				values.insert(name.clone(), Value::String("X".repeat(max_length + 1,),),);
+				format!("longer than maxLength {max_length}")
+			},
+			ViolationKind::NotInPicklist => {
+				values.insert(name.clone(), Value::String("__invalid__".to_string(),),);
+				"not a picklist value".to_string()
+			},
+			ViolationKind::TooManyDigits(integer_digits,) => {
+				let number = 10u64.pow((*integer_digits).min(18,) as u32,);
+				values.insert(name.clone(), Value::from(number,),);
+				format!("more than {integer_digits} integer digits")
+			},
+			ViolationKind::WrongType => {
+				values.insert(name.clone(), Value::String("__invalid__".to_string(),),);
+				format!("not a {}", metadata.type_on_raml.to_string())
+			},
+		};
+		Some(Violation { field: name, message, },)
+	}
+}
+
+enum ViolationKind {
+	MissingRequired,
+	ReadOnly,
+	TooLong(usize,),
+	NotInPicklist,
+	TooManyDigits(usize,),
+	WrongType,
+}
+
+fn violations(metadata: &RamlTypesMetadata,) -> Vec<(&RamlTypesMetadata, ViolationKind,),> {
+	let mut kinds = vec![];
+	if metadata.read_only {
+		kinds.push(ViolationKind::ReadOnly,);
+		return kinds.into_iter().map(|kind| (metadata, kind,),).collect();
+	}
+
+	if metadata.required {
+		kinds.push(ViolationKind::MissingRequired,);
+	}
+	if let Some(max_length,) = metadata.max_length {
+		kinds.push(ViolationKind::TooLong(max_length,),);
+	}
+	match &metadata.type_on_raml {
+		RamlType::Enum(values, _,) if !values.is_empty() => {
+			kinds.push(ViolationKind::NotInPicklist,)
+		},
+		RamlType::Number => {
+			if let Some(precision,) = metadata.precision {
+				let integer_digits = precision.saturating_sub(metadata.scale.unwrap_or_default(),);
+				kinds.push(ViolationKind::TooManyDigits(integer_digits,),);
+			}
+			kinds.push(ViolationKind::WrongType,);
+		},
+		RamlType::Boolean | RamlType::Date | RamlType::DateTime => {
+			kinds.push(ViolationKind::WrongType,)
+		},
+		_ => (),
+	}
+	kinds.into_iter().map(|kind| (metadata, kind,),).collect()
+}
+
+pub fn create_fake_data_file(
+	data: &RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	records: &[FakeRecord],
+	format: FakeDataFormat,
+) -> Rslt<(),> {
+	std::fs::write(filename, render_fake_data(data, records, format,),)?;
+	Ok((),)
+}
+
+pub fn render_fake_data(
+	data: &RamlMetadataStream,
+	records: &[FakeRecord],
+	format: FakeDataFormat,
+) -> String {
+	match format {
+		FakeDataFormat::JsonLines => {
+			let line = |record: &FakeRecord| Value::Object(record.values.clone(),).to_string();
+			records.iter().map(|record| line(record,) + "\n",).collect()
+		},
+		FakeDataFormat::BulkCsv => to_bulk_csv(data, records,),
+	}
+}
+
+/// header of api names, then one row per record. fields no record sets are left out, empty cells
+/// leave the field unset
+fn to_bulk_csv(data: &RamlMetadataStream, records: &[FakeRecord],) -> String {
+	let columns: Vec<&str,> = data
+		.fields()
+		.iter()
+		.map(|metadata| metadata.name.as_str(),)
+		.filter(|name| records.iter().any(|record| record.values.contains_key(*name,),),)
+		.collect();
+
+	let mut lines = vec![columns.join(",",)];
+	for record in records {
+		let cells: Vec<String,> =
+			columns.iter().map(|column| value_cell(record.values.get(*column,),),).collect();
+		lines.push(cells.join(",",),);
+	}
+	lines.join("\n",) + "\n"
+}
+
+/// csv cell of a json value. missing and null values are empty cells
+pub(crate) fn value_cell(value: Option<&Value,>,) -> String {
+	match value {
+		Some(Value::String(text,),) => csv_cell(text,),
+		Some(Value::Null,) | None => String::new(),
+		Some(value,) => value.to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::json_schema::object_schema;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_valid_records() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let records = FakeDataGenerator::new(&raml_stream, 5,).records(200,);
+		assert_eq!(records, FakeDataGenerator::new(&raml_stream, 5,).records(200,));
+
+		let validator = jsonschema::draft202012::new(&object_schema(&raml_stream,),)?;
+		for record in &records {
+			let values = Value::Object(record.values.clone(),);
+			assert!(validator.is_valid(&values), "{values}");
+			assert!(record.violation.is_none());
+
+			let quantity = record.values["Quantity__c"].as_i64().unwrap();
+			assert!((0..10_000).contains(&quantity));
+			if let Some(unit_price,) = record.values.get("UnitPrice__c",) {
+				assert!(unit_price.as_f64().unwrap() < 1e8);
+			}
+		}
+		Ok((),)
+	}
+
+	#[test]
+	fn test_invalid_records() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let records = FakeDataGenerator::new(&raml_stream, 5,).with_invalid_records().records(100,);
+
+		let validator = jsonschema::draft202012::new(&object_schema(&raml_stream,),)?;
+		for record in &records {
+			let violation = record.violation.as_ref().unwrap();
+			let values = Value::Object(record.values.clone(),);
+			// precision, readOnly and formats are not checked by the schema validator
+			let undetected = ["integer digits", "read-only", "not a date"];
+			if !undetected.iter().any(|message| violation.message.contains(message,),) {
+				assert!(!validator.is_valid(&values), "{violation:?}: {values}");
+			}
+		}
+		Ok((),)
+	}
+
+	#[test]
+	fn test_render_bulk_csv() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?
+			.filter(|metadata| matches!(metadata.name.as_str(), "AccessCode__c" | "Quantity__c"),);
+		let record = |access_code: Option<&str,>| FakeRecord {
+			values:    access_code
+				.map(|code| ("AccessCode__c".to_string(), Value::from(code,),),)
+				.into_iter()
+				.chain([("Quantity__c".to_string(), Value::from(3,),)],)
+				.collect(),
+			violation: None,
+		};
+		let records = [record(Some("a,\"b\"",),), record(None,)];
+
+		let csv = render_fake_data(&raml_stream, &records, FakeDataFormat::BulkCsv,);
+		assert_eq!(csv, "AccessCode__c,Quantity__c\n\"a,\"\"b\"\"\",3\n,3\n");
+		let jsonl = render_fake_data(&raml_stream, &records, FakeDataFormat::JsonLines,);
+		assert_eq!(jsonl.lines().nth(1), Some(r#"{"Quantity__c":3}"#));
+		Ok((),)
+	}
+}
@@ -0,0 +1,203 @@
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use anyhow::Result as Rslt;
+use anyhow::bail;
+use std::path::Path;
+use std::str::FromStr;
+
+const COLUMNS: [&str; 8] = [
+	"API Name",
+	"Label",
+	"Description",
+	"Type",
+	"Length",
+	"Required",
+	"Picklist Values",
+	"Reference To",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default,)]
+pub enum DocsFormat {
+	#[default]
+	Markdown,
+	/// single page with a search box, no external assets
+	Html,
+}
+
+impl FromStr for DocsFormat {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str,) -> Rslt<Self,> {
+		match s {
+			"md" => Ok(Self::Markdown,),
+			"html" => Ok(Self::Html,),
+			_ => bail!("unknown format `{s}`. expect md or html"),
+		}
+	}
+}
+
+impl RamlMetadataStream {
+	pub fn create_field_docs_file(self, filename: String, format: DocsFormat,) -> Rslt<(),> {
+		create_field_docs_file(&self, format!("data/{filename}"), format,)
+	}
+}
+
+pub fn create_field_docs_file(
+	data: &RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	format: DocsFormat,
+) -> Rslt<(),> {
+	std::fs::write(filename, render_field_docs(data, format,),)?;
+	Ok((),)
+}
+
+/// field dictionary of the object, one row per field in the order of the metadata
+pub fn render_field_docs(data: &RamlMetadataStream, format: DocsFormat,) -> String {
+	match format {
+		DocsFormat::Markdown => render_markdown(data,),
+		DocsFormat::Html => render_html(data,),
+	}
+}
+
+/// cells of a field in the order of [`COLUMNS`]. multi-valued cells are separated by line breaks
+fn field_row(metadata: &RamlTypesMetadata,) -> [String; 8] {
+	let mut sf_type = metadata.sf_type.clone();
+	if metadata.read_only {
+		sf_type.push_str(" (read-only)",);
+	}
+	let picklist_values = match &metadata.type_on_raml {
+		RamlType::Enum(values, _,) => values.join("\n",),
+		_ => String::new(),
+	};
+	[
+		metadata.name.clone(),
+		metadata.desc.clone(),
+		metadata.description.clone().unwrap_or_default(),
+		sf_type,
+		length_text(metadata,),
+		if metadata.required { "yes" } else { "" }.to_string(),
+		picklist_values,
+		metadata.reference_to.join("\n",),
+	]
+}
+
+/// max length of text fields, `precision, scale` of number fields
+pub(crate) fn length_text(metadata: &RamlTypesMetadata,) -> String {
+	match (metadata.max_length, metadata.precision, metadata.scale,) {
+		(Some(max_length,), _, _,) => max_length.to_string(),
+		(None, Some(precision,), scale,) => format!("{precision}, {}", scale.unwrap_or_default()),
+		(None, None, _,) => String::new(),
+	}
+}
+
+fn render_markdown(data: &RamlMetadataStream,) -> String {
+	let row = |cells: &[String]| format!("| {} |\n", cells.join(" | ",));
+	let mut lines = format!("# {}\n\n", data.object_name());
+	lines += &row(&COLUMNS.map(String::from,),);
+	lines += &row(&COLUMNS.map(|_| "---".to_string(),),);
+	for metadata in data.fields() {
+		let cells = field_row(metadata,).map(|cell| cell.replace('|', "\\|",),);
+		lines += &row(&cells.map(|cell| cell.replace('\n', "<br>",),),);
+	}
+	lines
+}
+
+fn render_html(data: &RamlMetadataStream,) -> String {
+	let object_name = escape_html(data.object_name(),);
+	let header: String = COLUMNS.iter().map(|column| format!("<th>{column}</th>"),).collect();
+	let rows: String = data
+		.fields()
+		.iter()
+		.map(|metadata| {
+			let cells: String = field_row(metadata,)
+				.iter()
+				.map(|cell| format!("<td>{}</td>", escape_html(cell,).replace('\n', "<br>",)),)
+				.collect();
+			format!("      <tr>{cells}</tr>\n")
+		},)
+		.collect();
+
+	format!(
+		r#"<!DOCTYPE html>
+<html>
+<head>
+  <meta charset="utf-8">
+  <title>{object_name}</title>
+  <style>
+    body {{ font-family: sans-serif; }}
+    table {{ border-collapse: collapse; }}
+    th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}
+  </style>
+</head>
+<body>
+  <h1>{object_name}</h1>
+  <input id="search" type="search" placeholder="Search fields" autofocus>
+  <table>
+    <thead><tr>{header}</tr></thead>
+    <tbody id="fields">
+{rows}    </tbody>
+  </table>
+  <script>
+    document.getElementById("search").addEventListener("input", (event) => {{
+      const query = event.target.value.toLowerCase();
+      for (const row of document.getElementById("fields").rows) {{
+        row.hidden = !row.textContent.toLowerCase().includes(query);
+      }}
+    }});
+  </script>
+</body>
+</html>
+"#
+	)
+}
+
+fn escape_html(text: &str,) -> String {
+	text.replace('&', "&amp;",).replace('<', "&lt;",).replace('>', "&gt;",).replace('"', "&quot;",)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_render_markdown() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let markdown = render_field_docs(&raml_stream, DocsFormat::Markdown,);
+		let lines: Vec<_,> = markdown.lines().collect();
+		assert_eq!(lines[0], "# Sample__c");
+		assert_eq!(
+			lines[2],
+			"| API Name | Label | Description | Type | Length | Required | Picklist Values | \
+			 Reference To |"
+		);
+		assert_eq!(lines.len(), 4 + raml_stream.fields().len());
+		assert_eq!(
+			lines[4],
+			"| AccessCode__c | 電子契約-アクセスコード | 電子契約サービスから払い出される \\| \
+			 区切りなしの照会コード | Text | 18 |  |  |  |"
+		);
+		assert!(lines.contains(&"| Account__c | 取引先 |  | Lookup | 18 |  |  | Account |"));
+		assert!(lines.contains(&"| Quantity__c | 数量 |  | Number | 4, 0 | yes |  |  |"));
+		let row = |name: &str| lines.iter().find(|line| line.starts_with(&format!("| {name} |"),),);
+		assert!(row("Agreement__c").unwrap().contains("| 89：一般<br>90：一般（フレックス）<br>"));
+		assert!(row("TotalAmount__c").unwrap().contains("| Number (read-only) |"));
+		Ok((),)
+	}
+
+	#[test]
+	fn test_render_html() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let html = render_field_docs(&raml_stream, DocsFormat::Html,);
+		assert!(html.starts_with("<!DOCTYPE html>\n"));
+		assert!(html.contains("<input id=\"search\""));
+		assert_eq!(html.matches("<tr><td>",).count(), raml_stream.fields().len());
+		assert!(html.contains("<td>Account__c</td><td>取引先</td>"));
+		let escaped = "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;";
+		assert_eq!(escape_html("<a href=\"x\">&</a>",), escaped);
+		Ok((),)
+	}
+}
@@ -0,0 +1,176 @@
+use crate::config::parse_bool;
+use crate::raml::RamlTypesMetadata;
+use anyhow::Result as Rslt;
+use regex::Regex;
+
+/// pattern on api names. `/.../` is a regex, anything else is a glob (`*`, `?`, `[...]`)
+#[derive(Debug, Clone,)]
+pub enum NamePattern {
+	Glob(glob::Pattern,),
+	Regex(Regex,),
+}
+
+impl NamePattern {
+	pub fn new(pattern: &str,) -> Rslt<Self,> {
+		let rslt = match pattern.strip_prefix('/',).and_then(|p| p.strip_suffix('/',),) {
+			Some(regex,) => Self::Regex(Regex::new(regex,)?,),
+			None => Self::Glob(glob::Pattern::new(pattern,)?,),
+		};
+		Ok(rslt,)
+	}
+
+	pub fn is_match(&self, name: &str,) -> bool {
+		match self {
+			NamePattern::Glob(pattern,) => pattern.matches(name,),
+			NamePattern::Regex(regex,) => regex.is_match(name,),
+		}
+	}
+
+	pub fn as_str(&self,) -> &str {
+		match self {
+			NamePattern::Glob(pattern,) => pattern.as_str(),
+			NamePattern::Regex(regex,) => regex.as_str(),
+		}
+	}
+}
+
+impl PartialEq for NamePattern {
+	fn eq(&self, other: &Self,) -> bool {
+		let same_kind = matches!(
+			(self, other,),
+			(NamePattern::Glob(_,), NamePattern::Glob(_,))
+				| (NamePattern::Regex(_,), NamePattern::Regex(_,))
+		);
+		same_kind && self.as_str() == other.as_str()
+	}
+}
+
+impl Eq for NamePattern {}
+
+/// declarative rules deciding which fields end up in generated types. every rule has to pass.
+/// empty `include_*` lists keep everything
+#[derive(Debug, Default, Clone, PartialEq, Eq,)]
+pub struct FieldFilter {
+	pub include_fields:     Vec<NamePattern,>,
+	pub exclude_fields:     Vec<NamePattern,>,
+	/// salesforce field types such as `Text` or `Lookup`
+	pub include_types:      Vec<String,>,
+	pub exclude_types:      Vec<String,>,
+	/// managed package namespace prefixes. fields without namespace never match
+	pub include_namespaces: Vec<String,>,
+	pub exclude_namespaces: Vec<String,>,
+	pub required_only:      bool,
+}
+
+impl FieldFilter {
+	pub fn matches(&self, metadata: &RamlTypesMetadata,) -> bool {
+		let name = metadata.name.as_str();
+		let namespace = namespace_prefix(name,);
+		let in_namespaces = |namespaces: &Vec<String,>| {
+			namespace.is_some_and(|ns| namespaces.iter().any(|n| n == ns,),)
+		};
+
+		(self.include_fields.is_empty()
+			|| self.include_fields.iter().any(|pattern| pattern.is_match(name,),))
+			&& !self.exclude_fields.iter().any(|pattern| pattern.is_match(name,),)
+			&& (self.include_types.is_empty() || self.include_types.contains(&metadata.sf_type,))
+			&& !self.exclude_types.contains(&metadata.sf_type,)
+			&& (self.include_namespaces.is_empty() || in_namespaces(&self.include_namespaces,))
+			&& !in_namespaces(&self.exclude_namespaces,)
+			&& (!self.required_only || metadata.required)
+	}
+
+	/// applies one rule. `key` is the field name of this struct. list values are comma separated
+	/// and extend rules already set. returns `false` for unknown keys
+	pub fn set(&mut self, key: &str, value: &str,) -> Rslt<bool,> {
+		let list = || value.split(',',).map(|s| s.trim(),).filter(|s| !s.is_empty(),);
+		let strings = || list().map(|s| s.to_string(),);
+		let patterns = || list().map(NamePattern::new,).collect::<Rslt<Vec<_,>,>>();
+
+		match key {
+			"include_fields" => self.include_fields.extend(patterns()?,),
+			"exclude_fields" => self.exclude_fields.extend(patterns()?,),
+			"include_types" => self.include_types.extend(strings(),),
+			"exclude_types" => self.exclude_types.extend(strings(),),
+			"include_namespaces" => self.include_namespaces.extend(strings(),),
+			"exclude_namespaces" => self.exclude_namespaces.extend(strings(),),
+			"required_only" => self.required_only = parse_bool(key, value,)?,
+			_ => return Ok(false,),
+		}
+		Ok(true,)
+	}
+}
+
+/// `ns__Field__c` -> `ns`. unmanaged and standard fields have no prefix
+pub fn namespace_prefix(name: &str,) -> Option<&str,> {
+	let mut parts = name.split("__",);
+	let prefix = parts.next()?;
+	if parts.count() >= 2 { Some(prefix,) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::RamlMetadataStream;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	fn names(stream: &RamlMetadataStream,) -> Vec<&str,> {
+		stream.fields().iter().map(|raml| raml.name.as_str(),).collect()
+	}
+
+	#[test]
+	fn test_namespace_prefix() {
+		assert_eq!(namespace_prefix("ns__Field__c"), Some("ns"));
+		assert_eq!(namespace_prefix("Field__c"), None);
+		assert_eq!(namespace_prefix("Name"), None);
+	}
+
+	#[test]
+	fn test_name_pattern() -> Rslt<(),> {
+		assert!(NamePattern::new("Access*",)?.is_match("AccessCode__c"));
+		assert!(!NamePattern::new("Access*",)?.is_match("Agreement__c"));
+		assert!(NamePattern::new("/^(Quantity|UnitPrice)__c$/",)?.is_match("UnitPrice__c"));
+		assert!(NamePattern::new("/[/",).is_err());
+		Ok((),)
+	}
+
+	#[test]
+	fn test_filter_fields() -> Rslt<(),> {
+		let mut field_filter = FieldFilter::default();
+		field_filter.set("include_fields", "*__c",)?;
+		field_filter.set("exclude_fields", "Contract*, /^Total/",)?;
+		field_filter.set("exclude_types", "Date",)?;
+		field_filter.set("required_only", "false",)?;
+		assert!(!field_filter.set("unknown", "",)?);
+
+		let stream = create_raml_metadata_stream(SAMPLE_PATH,)?.filter_fields(&field_filter,);
+		assert_eq!(names(&stream,), [
+			"AccessCode__c",
+			"Agreement__c",
+			"Account__c",
+			"Quantity__c",
+			"UnitPrice__c",
+			"IsSigned__c",
+		]);
+
+		field_filter.set("required_only", "true",)?;
+		let stream = create_raml_metadata_stream(SAMPLE_PATH,)?.filter_fields(&field_filter,);
+		assert_eq!(names(&stream,), ["Agreement__c", "Quantity__c"]);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_filter_composes_with_csv_rows() -> Rslt<(),> {
+		let mut field_filter = FieldFilter::default();
+		field_filter.set("include_types", "Number",)?;
+
+		let rows = vec!["Quantity__c".to_string(), "AccessCode__c".to_string()];
+		let stream = create_raml_metadata_stream(SAMPLE_PATH,)?
+			.filter_required_rows(rows,)
+			.filter_fields(&field_filter,);
+		assert_eq!(names(&stream,), ["Quantity__c"]);
+		Ok((),)
+	}
+}
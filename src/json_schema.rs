@@ -0,0 +1,160 @@
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use anyhow::Result as Rslt;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use std::path::Path;
+
+const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+impl RamlMetadataStream {
+	pub fn create_json_schema_file(self, filename: String,) -> Rslt<(),> {
+		create_json_schema_file(self, format!("data/{filename}"),)
+	}
+}
+
+pub fn create_json_schema_file(
+	data: RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+) -> Rslt<(),> {
+	let contents = serde_json::to_string_pretty(&object_schema(&data,),)?;
+	std::fs::write(filename, contents,)?;
+	Ok((),)
+}
+
+/// schema of a whole record. required properties follow the `required` flag of each field
+pub fn object_schema(data: &RamlMetadataStream,) -> Value {
+	let mut schema = json!({ "$schema": JSON_SCHEMA_DIALECT });
+	if !data.object_name().is_empty() {
+		schema["title"] = json!(data.object_name());
+	}
+
+	let properties = properties_schema(
+		data.fields().iter().map(|metadata| (metadata, metadata.required,),),
+	);
+	if let (Value::Object(schema,), Value::Object(properties,),) = (&mut schema, properties,) {
+		schema.extend(properties,);
+	}
+	schema
+}
+
+/// object schema of the given fields. each field comes with whether it is required
+pub fn properties_schema<'a,>(
+	fields: impl Iterator<Item = (&'a RamlTypesMetadata, bool,),>,
+) -> Value {
+	let mut properties = Map::new();
+	let mut required = vec![];
+	for (metadata, is_required,) in fields {
+		properties.insert(metadata.name.clone(), field_schema(metadata,),);
+		if is_required {
+			required.push(metadata.name.clone(),);
+		}
+	}
+
+	json!({
+		"type": "object",
+		"properties": properties,
+		"required": required,
+	})
+}
+
+/// schema of a single field. carries the same facets as `RamlTypesMetadata::format_as_raml`
+pub fn field_schema(metadata: &RamlTypesMetadata,) -> Value {
+	let mut schema = type_schema(&metadata.type_on_raml,);
+	schema["description"] = json!(metadata.desc);
+	if let Some(max_length,) = metadata.max_length {
+		schema["maxLength"] = json!(max_length);
+	}
+	if let Some(example,) = parse_example(&metadata.example,) {
+		schema["examples"] = json!([example]);
+	}
+	if metadata.read_only {
+		schema["readOnly"] = json!(true);
+	}
+	schema
+}
+
+fn type_schema(raml_type: &RamlType,) -> Value {
+	match raml_type {
+		RamlType::String => json!({ "type": "string" }),
+		RamlType::Number => json!({ "type": "number" }),
+		RamlType::Enum(items, raml_type,) => {
+			let mut schema = type_schema(raml_type,);
+			// a picklist without values takes any value of its base type, like its example
+			if !items.is_empty() {
+				schema["enum"] = json!(items);
+			}
+			schema
+		},
+		RamlType::Boolean => json!({ "type": "boolean" }),
+		RamlType::Date => json!({ "type": "string", "format": "date" }),
+		RamlType::DateTime => json!({ "type": "string", "format": "date-time" }),
+		RamlType::Any => json!({}),
+	}
+}
+
+/// examples are kept as raml (yaml) scalars such as `"XXX"`, `0` or `true`, which are also json.
+/// empty examples give `None`
+pub fn parse_example(example: &str,) -> Option<Value,> {
+	serde_json::from_str(example,).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::example::default_example;
+	use crate::raml::create_raml_metadata_stream;
+	use anyhow::anyhow;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	fn validator(schema: &Value,) -> Rslt<jsonschema::Validator,> {
+		jsonschema::draft202012::new(schema,).map_err(|e| anyhow!("{e}"),)
+	}
+
+	#[test]
+	fn test_field_schema() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let agreement = raml_stream.fields().iter().find(|raml| raml.name == "Agreement__c",);
+
+		let schema = field_schema(agreement.unwrap(),);
+		assert_eq!(
+			schema,
+			json!({
+				"type": "string",
+				"enum": ["89：一般", "90：一般（フレックス）", "93：適用除外業務"],
+				"description": "36協定区分",
+				"examples": ["89：一般"],
+			})
+		);
+
+		let mut no_values = agreement.unwrap().clone();
+		no_values.type_on_raml = RamlType::Enum(vec![], Box::new(RamlType::String,),);
+		no_values.example = default_example(&no_values.type_on_raml, no_values.max_length,);
+		let schema = field_schema(&no_values,);
+		assert_eq!(schema["enum"], Value::Null);
+		assert!(validator(&schema,)?.is_valid(&parse_example(&no_values.example,).unwrap()));
+		Ok((),)
+	}
+
+	#[test]
+	fn test_examples_conform_to_schema() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+
+		let mut record = Map::new();
+		for metadata in raml_stream.fields() {
+			let schema = field_schema(metadata,);
+			let Some(example,) = parse_example(&metadata.example,) else { continue };
+			assert!(validator(&schema,)?.is_valid(&example), "{}: {example}", metadata.name);
+			record.insert(metadata.name.clone(), example,);
+		}
+
+		let schema = object_schema(&raml_stream,);
+		assert_eq!(schema["required"], json!(["Agreement__c", "Quantity__c"]));
+		assert!(validator(&schema,)?.is_valid(&Value::Object(record,)));
+		assert!(!validator(&schema,)?.is_valid(&json!({ "Quantity__c": 1 })));
+		Ok((),)
+	}
+}
@@ -2,8 +2,31 @@
 
 //  TODO: - [x] required, null指定はいらない
 
+pub mod config;
 pub mod csv;
+pub mod data_csv;
+pub mod description;
+pub mod er_diagram;
+pub mod example;
+pub mod example_record;
+pub mod fake_data;
+pub mod field_docs;
+pub mod field_filter;
+pub mod json_schema;
+pub mod metadata_diff;
+pub mod mock_server;
+pub mod openapi;
+pub mod payload;
 pub mod raml;
+pub mod raml_check;
+pub mod raml_merge;
+pub mod raml_parser;
+pub mod rng;
+pub mod rust_struct;
+pub mod spec_sheet;
+pub mod system_field;
+pub mod translation;
+pub mod typescript;
 
 use anyhow::Result as Rslt;
 use std::path::Path;
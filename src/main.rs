@@ -1,6 +1,34 @@
 use anyhow::Result as Rslt;
+use anyhow::anyhow;
+use anyhow::bail;
+use xraml::config::Config;
+use xraml::csv::parse_as_csv;
+use xraml::csv::parse_property_file;
 use xraml::csv::read_as_csv;
+use xraml::csv::read_property_file;
+use xraml::data_csv::CsvTemplate;
+use xraml::data_csv::validate_data_csv;
+use xraml::er_diagram::DiagramFormat;
+use xraml::er_diagram::render_er_diagram;
+use xraml::example_record::ExampleRecords;
+use xraml::example_record::render_raml_with_examples;
+use xraml::fake_data::FakeDataFormat;
+use xraml::fake_data::FakeDataGenerator;
+use xraml::fake_data::render_fake_data;
+use xraml::field_docs::DocsFormat;
+use xraml::field_docs::render_field_docs;
+use xraml::metadata_diff::MetadataDiff;
+use xraml::metadata_diff::read_metadata_stream;
+use xraml::mock_server::MockServer;
+use xraml::payload::PayloadMode;
+use xraml::payload::validate_payload_document;
+use xraml::raml::GenerationMode;
 use xraml::raml::create_raml_metadata_stream;
+use xraml::read_file;
+use xraml::rng::Rng;
+use xraml::spec_sheet::dropped_rows;
+use xraml::spec_sheet::render_spec_csv;
+use xraml::translation::translated_filename;
 
 const INDIVIDUAL_CONTRACT_OBJ_PATH: &str = "data/IndividualContract__c.object";
 const SOEC_OBJ_PATH: &str = "data/SalesOrderEmploymentConditions__c.object";
@@ -8,18 +36,441 @@ const IC_RAML: &str = "individual_contract.raml";
 const SOEC_RAML: &str = "sales_order_employment_conditions.raml";
 const IC_CSV: &str = "data/kobetu.csv";
 const SOEC_CSV: &str = "data/keiyaku.csv";
+const CONFIG_PATH: &str = "data/xraml.conf";
+/// options given without value
+const BOOL_FLAGS: &[&str] = &["required_only", "merge",];
+
+/// `--config <path>`, `--check`, `--merge` and any config key such as `--mode crud`,
+/// `--exclude-fields 'Internal*'` or `--required-only`. list rules are added to those of the
+/// config file. the flag tells whether `--check` was given
+fn parse_args(mut args: impl Iterator<Item = String,>,) -> Rslt<(Config, bool,),> {
+	let mut config_path = CONFIG_PATH.to_string();
+	let mut check = false;
+	let mut options = vec![];
+
+	while let Some(arg,) = args.next() {
+		let Some(flag,) = arg.strip_prefix("--",) else { bail!("unexpected argument `{arg}`") };
+		let key = flag.replace('-', "_",);
+		if key == "check" {
+			check = true;
+			continue;
+		}
+		if BOOL_FLAGS.contains(&key.as_str(),) {
+			options.push((arg, key, "true".to_string(),),);
+			continue;
+		}
+
+		let value = args.next().ok_or(anyhow!("`{arg}` expects a value"),)?;
+		if key == "config" {
+			config_path = value;
+		} else {
+			options.push((arg, key, value,),);
+		}
+	}
+
+	let mut config = Config::load_or_default(config_path,)?;
+	for (arg, key, value,) in options {
+		if !config.set(&key, &value,)? {
+			bail!("unknown option `{arg}`")
+		}
+	}
+	Ok((config, check,),)
+}
+
+/// `diff <old> <new> [--json]` compares `.object` metadata or `.raml` libraries generated in
+/// `request_response` or `crud` mode. exits with 1 if a change breaks clients
+fn diff(args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let (flags, paths,): (Vec<_,>, Vec<_,>,) = args.partition(|arg| arg.starts_with("--",),);
+	if let Some(flag,) = flags.iter().find(|flag| *flag != "--json",) {
+		bail!("unknown option `{flag}`")
+	}
+	let [old, new,] = paths.as_slice() else { bail!("usage: xraml diff <old> <new> [--json]") };
+
+	let diff = MetadataDiff::new(&read_metadata_stream(old,)?, &read_metadata_stream(new,)?,);
+	if flags.is_empty() {
+		print!("{}", diff.to_text());
+	} else {
+		println!("{:#}", diff.to_json());
+	}
+	if diff.has_breaking_change() {
+		std::process::exit(1,);
+	}
+	Ok((),)
+}
+
+/// `docs <object> [--format md|html] [--language <lang>]` prints the field dictionary of a
+/// `.object` file or a generated `.raml` library. `--language` takes labels and help texts from
+/// the `objectTranslations` of the project
+fn docs(mut args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let mut path = None;
+	let mut format = DocsFormat::default();
+	let mut language = None;
+	while let Some(arg,) = args.next() {
+		let mut value = || args.next().ok_or(anyhow!("`{arg}` expects a value"),);
+		match arg.as_str() {
+			"--format" => format = value()?.parse()?,
+			"--language" => language = Some(value()?,),
+			flag if flag.starts_with("--",) => bail!("unknown option `{flag}`"),
+			_ => path = Some(arg,),
+		}
+	}
+	let path = path.ok_or(anyhow!(
+		"usage: xraml docs <object> [--format md|html] [--language <lang>]"
+	),)?;
+
+	let mut stream = read_metadata_stream(&path,)?;
+	if let Some(language,) = language {
+		let languages = [language.clone()];
+		stream = stream.load_translations(&path, &languages,)?.translated(&language,);
+	}
+	print!("{}", render_field_docs(&stream, format,));
+	Ok((),)
+}
+
+/// `er <object> [--spec <csv>]... [--format mermaid|dot]` prints the relationships between the
+/// objects. `--spec` limits the fields of the object before it to those the spec csv selects
+fn er(mut args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let mut objects: Vec<(String, Option<String,>,),> = vec![];
+	let mut format = DiagramFormat::default();
+	while let Some(arg,) = args.next() {
+		let mut value = || args.next().ok_or(anyhow!("`{arg}` expects a value"),);
+		match arg.as_str() {
+			"--format" => format = value()?.parse()?,
+			"--spec" => {
+				let spec = value()?;
+				let Some((_, object_spec,),) = objects.last_mut() else {
+					bail!("`--spec` has to follow an object")
+				};
+				object_spec.replace(spec,);
+			},
+			flag if flag.starts_with("--",) => bail!("unknown option `{flag}`"),
+			_ => objects.push((arg, None,),),
+		}
+	}
+	if objects.is_empty() {
+		bail!("usage: xraml er <object> [--spec <csv>]... [--format mermaid|dot]")
+	}
+
+	let streams = objects
+		.into_iter()
+		.map(|(path, spec,)| {
+			let stream = create_raml_metadata_stream(path,)?;
+			match spec {
+				Some(spec,) => {
+					let rows = parse_as_csv(&read_file(spec,)?,)?.acquire_required_rows_name();
+					Ok(stream.filter_required_rows(rows,),)
+				},
+				None => Ok(stream,),
+			}
+		},)
+		.collect::<Rslt<Vec<_,>,>>()?;
+	print!("{}", render_er_diagram(&streams, format,));
+	Ok((),)
+}
+
+/// `examples <object> [--seed <n>] [--raml]` prints sample request bodies of the object, or its
+/// crud library carrying them. examples of the property file win over random values
+fn examples(mut args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let mut path = None;
+	let mut seed = 0;
+	let mut raml = false;
+	while let Some(arg,) = args.next() {
+		match arg.as_str() {
+			"--seed" => seed = args.next().ok_or(anyhow!("`--seed` expects a value"),)?.parse()?,
+			"--raml" => raml = true,
+			flag if flag.starts_with("--",) => bail!("unknown option `{flag}`"),
+			_ => path = Some(arg,),
+		}
+	}
+	let path = path.ok_or(anyhow!("usage: xraml examples <object> [--seed <n>] [--raml]"),)?;
+
+	// the property file is optional
+	let properties = read_property_file().map(|content| parse_property_file(&content,),);
+	let stream = create_raml_metadata_stream(path,)?;
+	let examples =
+		ExampleRecords::new(&stream, &properties.unwrap_or_default(), &mut Rng::new(seed,),)?;
+	if raml {
+		print!("{}", render_raml_with_examples(&stream, GenerationMode::Crud, &examples,)?);
+	} else {
+		print!("{}", examples.to_json_string()?);
+	}
+	Ok((),)
+}
+
+/// `fake <object> [--count <n>] [--seed <n>] [--format jsonl|csv] [--invalid]` prints test
+/// records. with `--invalid`, what each record breaks goes to stderr
+fn fake(mut args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let mut path = None;
+	let mut count = 100;
+	let mut seed = 0;
+	let mut format = FakeDataFormat::default();
+	let mut invalid = false;
+	while let Some(arg,) = args.next() {
+		let mut value = || args.next().ok_or(anyhow!("`{arg}` expects a value"),);
+		match arg.as_str() {
+			"--count" => count = value()?.parse()?,
+			"--seed" => seed = value()?.parse()?,
+			"--format" => format = value()?.parse()?,
+			"--invalid" => invalid = true,
+			flag if flag.starts_with("--",) => bail!("unknown option `{flag}`"),
+			_ => path = Some(arg,),
+		}
+	}
+	let path = path.ok_or(anyhow!(
+		"usage: xraml fake <object> [--count <n>] [--seed <n>] [--format jsonl|csv] [--invalid]"
+	),)?;
+
+	let stream = create_raml_metadata_stream(path,)?;
+	let mut generator = FakeDataGenerator::new(&stream, seed,);
+	if invalid {
+		generator = generator.with_invalid_records();
+	}
+	let records = generator.records(count,);
+	print!("{}", render_fake_data(&stream, &records, format,));
+	for (i, record,) in records.iter().enumerate() {
+		if let Some(violation,) = &record.violation {
+			eprintln!("record {}: {}: {}", i + 1, violation.field, violation.message);
+		}
+	}
+	Ok((),)
+}
+
+/// `serve <object>... [--port <n>] [--seed <n>]` runs a mock of the sobject rest api on
+/// localhost
+fn serve(mut args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let mut paths = vec![];
+	let mut port = 8080;
+	let mut seed = 0;
+	while let Some(arg,) = args.next() {
+		let mut value = || args.next().ok_or(anyhow!("`{arg}` expects a value"),);
+		match arg.as_str() {
+			"--port" => port = value()?.parse()?,
+			"--seed" => seed = value()?.parse()?,
+			flag if flag.starts_with("--",) => bail!("unknown option `{flag}`"),
+			_ => paths.push(arg,),
+		}
+	}
+	if paths.is_empty() {
+		bail!("usage: xraml serve <object>... [--port <n>] [--seed <n>]")
+	}
+
+	let objects = paths.iter().map(create_raml_metadata_stream,).collect::<Rslt<_,>>()?;
+	let properties = read_property_file().map(|content| parse_property_file(&content,),);
+	let mut server = MockServer::new(objects, &properties.unwrap_or_default(), seed,)?;
+	server.serve(("127.0.0.1", port,),)
+}
+
+/// `spec <object> [--previous <csv>] [--out <csv>]` writes the 項目一覧 sheet of an object.
+/// 〇 markers of fields still on the object are taken over from the previous sheet
+fn spec(mut args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let mut path = None;
+	let mut previous = None;
+	let mut out = None;
+	while let Some(arg,) = args.next() {
+		let mut value = || args.next().ok_or(anyhow!("`{arg}` expects a value"),);
+		match arg.as_str() {
+			"--previous" => previous = Some(value()?,),
+			"--out" => out = Some(value()?,),
+			flag if flag.starts_with("--",) => bail!("unknown option `{flag}`"),
+			_ => path = Some(arg,),
+		}
+	}
+	let path =
+		path.ok_or(anyhow!("usage: xraml spec <object> [--previous <csv>] [--out <csv>]"),)?;
+
+	let stream = create_raml_metadata_stream(path,)?;
+	let previous = previous.map(|path| parse_as_csv(&read_file(path,)?,),).transpose()?;
+	if let Some(previous,) = &previous {
+		for name in dropped_rows(&stream, previous,) {
+			eprintln!("{name} is no longer on {}, dropped from the sheet", stream.object_name());
+		}
+	}
+	let sheet = render_spec_csv(&stream, previous.as_ref(),);
+	match out {
+		Some(out,) => std::fs::write(out, sheet,)?,
+		None => print!("{sheet}"),
+	}
+	Ok((),)
+}
+
+/// `template <object> [--required-only] [--spec <csv>] [--labels] [--rows <n>] [--seed <n>]`
+/// prints a data loader csv to fill in. `--spec` orders the columns like the spec csv rows
+fn template(mut args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let mut path = None;
+	let mut required_only = false;
+	let mut spec = None;
+	let mut labels = false;
+	let mut rows = 0;
+	let mut seed = 0;
+	while let Some(arg,) = args.next() {
+		let mut value = || args.next().ok_or(anyhow!("`{arg}` expects a value"),);
+		match arg.as_str() {
+			"--required-only" => required_only = true,
+			"--spec" => spec = Some(value()?,),
+			"--labels" => labels = true,
+			"--rows" => rows = value()?.parse()?,
+			"--seed" => seed = value()?.parse()?,
+			flag if flag.starts_with("--",) => bail!("unknown option `{flag}`"),
+			_ => path = Some(arg,),
+		}
+	}
+	let path = path.ok_or(anyhow!(
+		"usage: xraml template <object> [--required-only] [--spec <csv>] [--labels] [--rows <n>] \
+		 [--seed <n>]"
+	),)?;
+
+	let stream = create_raml_metadata_stream(path,)?;
+	let mut template = CsvTemplate::new(&stream,).with_sample_rows(rows, seed,);
+	if required_only {
+		template = template.required_only();
+	}
+	if let Some(spec,) = spec {
+		template = template.in_order(read_as_csv(spec,)?.acquire_required_rows_name(),);
+	}
+	if labels {
+		template = template.with_label_row();
+	}
+	print!("{}", template.render());
+	Ok((),)
+}
+
+/// `validate --object <object> <payload> [--mode create|update]` checks a json record, or an
+/// array of them, the way salesforce would. a `.csv` payload is checked as a data loader file.
+/// exits with 1 on violations
+fn validate(mut args: impl Iterator<Item = String,>,) -> Rslt<(),> {
+	let mut object = None;
+	let mut payload = None;
+	let mut mode = PayloadMode::default();
+	while let Some(arg,) = args.next() {
+		let mut value = || args.next().ok_or(anyhow!("`{arg}` expects a value"),);
+		match arg.as_str() {
+			"--object" => object = Some(value()?,),
+			"--mode" => mode = value()?.parse()?,
+			flag if flag.starts_with("--",) => bail!("unknown option `{flag}`"),
+			_ => payload = Some(arg,),
+		}
+	}
+	let (Some(object,), Some(payload,),) = (object, payload,) else {
+		bail!("usage: xraml validate --object <object> <payload|data.csv> [--mode create|update]")
+	};
+
+	let stream = read_metadata_stream(object,)?;
+	if payload.ends_with(".csv",) {
+		let violations = validate_data_csv(&stream, &read_file(&payload,)?, mode,)?;
+		if violations.is_empty() {
+			println!("{payload}: valid");
+			return Ok((),);
+		}
+		violations.iter().for_each(|violation| println!("{payload}: {violation}"),);
+		std::process::exit(1,);
+	}
+
+	let document = serde_json::from_str(&read_file(&payload,)?,)
+		.map_err(|e| anyhow!("{payload} is not json: {e}"),)?;
+	let violations = validate_payload_document(&stream, &document, mode,);
+	if violations.is_empty() {
+		println!("{payload}: valid");
+		return Ok((),);
+	}
+
+	violations.iter().for_each(|violation| println!("{payload}#{violation}"),);
+	std::process::exit(1,);
+}
 
 fn main() -> Rslt<(),> {
+	let mut args = std::env::args().skip(1,).peekable();
+	if args.next_if_eq("diff",).is_some() {
+		return diff(args,);
+	}
+	if args.next_if_eq("docs",).is_some() {
+		return docs(args,);
+	}
+	if args.next_if_eq("er",).is_some() {
+		return er(args,);
+	}
+	if args.next_if_eq("examples",).is_some() {
+		return examples(args,);
+	}
+	if args.next_if_eq("fake",).is_some() {
+		return fake(args,);
+	}
+	if args.next_if_eq("serve",).is_some() {
+		return serve(args,);
+	}
+	if args.next_if_eq("spec",).is_some() {
+		return spec(args,);
+	}
+	if args.next_if_eq("template",).is_some() {
+		return template(args,);
+	}
+	if args.next_if_eq("validate",).is_some() {
+		return validate(args,);
+	}
+
+	let (config, check,) = parse_args(args,)?;
 	let content = vec![
 		(read_as_csv(IC_CSV,)?, INDIVIDUAL_CONTRACT_OBJ_PATH, IC_RAML,),
 		(read_as_csv(SOEC_CSV,)?, SOEC_OBJ_PATH, SOEC_RAML,),
 	];
 
+	let mut stale = false;
 	for (csv, obj_path, raml_file,) in content {
 		let acquired_rows = csv.acquire_required_rows_name();
-		create_raml_metadata_stream(obj_path,)?
-			.create_raml_file_minimal(acquired_rows, raml_file.to_string(),)?;
+		let overrides = match &config.descriptions.column {
+			Some(column,) => csv.column_values(column,),
+			None => vec![],
+		};
+		// system fields are not in the spec csv, so inject them after filtering
+		let stream = create_raml_metadata_stream(obj_path,)?
+			.filter_required_rows(acquired_rows,)
+			.filter_fields(&config.filter,)
+			.with_system_fields(&config.system_fields,)?
+			.load_translations(obj_path, &config.languages,)?;
+		println!("types of {raml_file}: {}", stream.fields().len());
+
+		// overrides of the spec csv are in the default language, translated libraries skip them
+		let translated = config.languages.iter().map(|language| {
+			let stream = stream.clone().translated(language,);
+			let filename = translated_filename(raml_file, language,);
+			(stream.compose_descriptions(&config.descriptions, &[],), filename,)
+		},);
+		let original = stream.clone().compose_descriptions(&config.descriptions, &overrides,);
+		let libraries: Vec<_,> =
+			std::iter::once((original, raml_file.to_string(),),).chain(translated,).collect();
+		for (stream, raml_file,) in libraries {
+			let (diff, conflicts,) = match (check, config.merge,) {
+				(true, true,) => stream.check_merged_raml_file(
+					raml_file.clone(),
+					config.mode,
+					&config.user_facets,
+				)?,
+				(true, false,) => {
+					(stream.check_raml_file(raml_file.clone(), config.mode,)?, vec![],)
+				},
+				(false, true,) => (
+					None,
+					stream.merge_raml_file(
+						raml_file.clone(),
+						config.mode,
+						&config.user_facets,
+					)?,
+				),
+				(false, false,) => {
+					stream.create_raml_file_with_mode(raml_file.clone(), config.mode,)?;
+					(None, vec![],)
+				},
+			};
+			conflicts.iter().for_each(|conflict| eprintln!("conflict in {raml_file}: {conflict}"),);
+			if let Some(diff,) = diff {
+				print!("{diff}");
+				stale = true;
+			}
+		}
 	}
 
+	if stale {
+		eprintln!("generated raml is out of date. run xraml without `--check` to regenerate");
+		std::process::exit(1,);
+	}
 	Ok((),)
 }
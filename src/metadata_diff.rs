@@ -0,0 +1,369 @@
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use crate::raml::create_raml_metadata_stream;
+use crate::raml_parser::read_raml_library;
+use anyhow::Result as Rslt;
+use anyhow::bail;
+use serde_json::Value;
+use serde_json::json;
+use std::fmt::Display;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub enum ChangeKind {
+	Added { required: bool, },
+	Removed,
+	TypeChanged { from: String, to: String, },
+	MaxLengthChanged { from: Option<usize,>, to: Option<usize,>, },
+	EnumValuesAdded(Vec<String,>,),
+	EnumValuesRemoved(Vec<String,>,),
+	RequiredChanged { to: bool, },
+	ReadOnlyChanged { to: bool, },
+	DescriptionChanged { from: String, to: String, },
+}
+
+/// change of a single field between two versions of an object
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct FieldChange {
+	pub field: String,
+	pub kind:  ChangeKind,
+}
+
+impl FieldChange {
+	/// whether clients written against the old version may fail against the new one
+	pub fn is_breaking(&self,) -> bool {
+		match &self.kind {
+			ChangeKind::Added { required, } => *required,
+			ChangeKind::Removed | ChangeKind::TypeChanged { .. } => true,
+			ChangeKind::MaxLengthChanged { from, to, } => match (from, to,) {
+				(_, None,) => false,
+				(None, Some(_,),) => true,
+				(Some(from,), Some(to,),) => to < from,
+			},
+			ChangeKind::EnumValuesAdded(_,) => false,
+			ChangeKind::EnumValuesRemoved(_,) => true,
+			ChangeKind::RequiredChanged { to, } | ChangeKind::ReadOnlyChanged { to, } => *to,
+			ChangeKind::DescriptionChanged { .. } => false,
+		}
+	}
+
+	/// camel case name of the change, used as `change` in json output
+	pub fn kind_name(&self,) -> &'static str {
+		match &self.kind {
+			ChangeKind::Added { .. } => "added",
+			ChangeKind::Removed => "removed",
+			ChangeKind::TypeChanged { .. } => "typeChanged",
+			ChangeKind::MaxLengthChanged { .. } => "maxLengthChanged",
+			ChangeKind::EnumValuesAdded(_,) => "enumValuesAdded",
+			ChangeKind::EnumValuesRemoved(_,) => "enumValuesRemoved",
+			ChangeKind::RequiredChanged { .. } => "requiredChanged",
+			ChangeKind::ReadOnlyChanged { .. } => "readOnlyChanged",
+			ChangeKind::DescriptionChanged { .. } => "descriptionChanged",
+		}
+	}
+
+	pub fn message(&self,) -> String {
+		let length = |l: &Option<usize,>| l.map_or("unlimited".to_string(), |l| l.to_string(),);
+		match &self.kind {
+			ChangeKind::Added { required: true, } => "added as required field".to_string(),
+			ChangeKind::Added { required: false, } => "added".to_string(),
+			ChangeKind::Removed => "removed".to_string(),
+			ChangeKind::TypeChanged { from, to, } => format!("type changed {from} -> {to}"),
+			ChangeKind::MaxLengthChanged { from, to, } => {
+				format!("maxLength changed {} -> {}", length(from), length(to))
+			},
+			ChangeKind::EnumValuesAdded(values,) => {
+				format!("enum values added: {}", values.join(", "))
+			},
+			ChangeKind::EnumValuesRemoved(values,) => {
+				format!("enum values removed: {}", values.join(", "))
+			},
+			ChangeKind::RequiredChanged { to: true, } => "became required".to_string(),
+			ChangeKind::RequiredChanged { to: false, } => "became optional".to_string(),
+			ChangeKind::ReadOnlyChanged { to: true, } => "became read-only".to_string(),
+			ChangeKind::ReadOnlyChanged { to: false, } => "became writable".to_string(),
+			ChangeKind::DescriptionChanged { from, to, } => {
+				format!("description changed {from:?} -> {to:?}")
+			},
+		}
+	}
+
+	pub fn to_json(&self,) -> Value {
+		let mut json = json!({
+			"field": self.field,
+			"change": self.kind_name(),
+			"breaking": self.is_breaking(),
+			"message": self.message(),
+		});
+		match &self.kind {
+			ChangeKind::TypeChanged { from, to, } | ChangeKind::DescriptionChanged { from, to, } => {
+				json["from"] = json!(from);
+				json["to"] = json!(to);
+			},
+			ChangeKind::MaxLengthChanged { from, to, } => {
+				json["from"] = json!(from);
+				json["to"] = json!(to);
+			},
+			ChangeKind::EnumValuesAdded(values,) | ChangeKind::EnumValuesRemoved(values,) => {
+				json["values"] = json!(values);
+			},
+			ChangeKind::Added { required: to, }
+			| ChangeKind::RequiredChanged { to, }
+			| ChangeKind::ReadOnlyChanged { to, } => {
+				json["to"] = json!(to);
+			},
+			ChangeKind::Removed => (),
+		}
+		json
+	}
+}
+
+impl Display for FieldChange {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_,>,) -> std::fmt::Result {
+		let level = if self.is_breaking() { "BREAKING" } else { "non-breaking" };
+		write!(f, "{level:<12} {}: {}", self.field, self.message())
+	}
+}
+
+/// changes of the api contract between two versions of an object
+#[derive(Debug, Clone, PartialEq, Eq, Default,)]
+pub struct MetadataDiff {
+	/// api name of the object, or the file stem of a library
+	pub object:  String,
+	pub changes: Vec<FieldChange,>,
+}
+
+impl MetadataDiff {
+	pub fn new(old: &RamlMetadataStream, new: &RamlMetadataStream,) -> Self {
+		let mut changes = vec![];
+
+		for old_field in old.fields() {
+			match new.fields().iter().find(|field| field.name == old_field.name,) {
+				Some(new_field,) => diff_field(old_field, new_field, &mut changes,),
+				None => changes.push(FieldChange {
+					field: old_field.name.clone(),
+					kind:  ChangeKind::Removed,
+				},),
+			}
+		}
+		for new_field in new.fields() {
+			if old.fields().iter().all(|field| field.name != new_field.name,) {
+				changes.push(FieldChange {
+					field: new_field.name.clone(),
+					kind:  ChangeKind::Added { required: new_field.required, },
+				},);
+			}
+		}
+
+		let object = match new.object_name() {
+			"" => old.object_name(),
+			object => object,
+		};
+		Self { object: object.to_string(), changes, }
+	}
+
+	pub fn has_breaking_change(&self,) -> bool {
+		self.changes.iter().any(FieldChange::is_breaking,)
+	}
+
+	/// the object, then one change per line, breaking ones first
+	pub fn to_text(&self,) -> String {
+		if self.changes.is_empty() {
+			return format!("{}: no changes\n", self.object);
+		}
+
+		let (breaking, non_breaking,): (Vec<_,>, Vec<_,>,) =
+			self.changes.iter().partition(|change| change.is_breaking(),);
+		let lines = breaking.iter().chain(non_breaking.iter(),);
+		let changes: String = lines.map(|change| format!("{change}\n"),).collect();
+		format!("{}:\n{changes}", self.object)
+	}
+
+	pub fn to_json(&self,) -> Value {
+		json!({
+			"object": self.object,
+			"breaking": self.has_breaking_change(),
+			"changes": self.changes.iter().map(FieldChange::to_json).collect::<Vec<_,>>(),
+		})
+	}
+}
+
+fn diff_field(old: &RamlTypesMetadata, new: &RamlTypesMetadata, changes: &mut Vec<FieldChange,>,) {
+	let mut push = |kind: ChangeKind| changes.push(FieldChange { field: new.name.clone(), kind, },);
+
+	let (old_type, new_type,) = (type_label(&old.type_on_raml,), type_label(&new.type_on_raml,),);
+	if old_type != new_type {
+		push(ChangeKind::TypeChanged { from: old_type, to: new_type, },);
+	} else if let (RamlType::Enum(old_values, _,), RamlType::Enum(new_values, _,),) =
+		(&old.type_on_raml, &new.type_on_raml,)
+	{
+		let removed: Vec<_,> =
+			old_values.iter().filter(|value| !new_values.contains(value,),).cloned().collect();
+		let added: Vec<_,> =
+			new_values.iter().filter(|value| !old_values.contains(value,),).cloned().collect();
+		if !removed.is_empty() {
+			push(ChangeKind::EnumValuesRemoved(removed,),);
+		}
+		if !added.is_empty() {
+			push(ChangeKind::EnumValuesAdded(added,),);
+		}
+	}
+
+	if old.max_length != new.max_length {
+		push(ChangeKind::MaxLengthChanged { from: old.max_length, to: new.max_length, },);
+	}
+	if old.required != new.required {
+		push(ChangeKind::RequiredChanged { to: new.required, },);
+	}
+	if old.read_only != new.read_only {
+		push(ChangeKind::ReadOnlyChanged { to: new.read_only, },);
+	}
+	if old.desc != new.desc {
+		push(ChangeKind::DescriptionChanged { from: old.desc.clone(), to: new.desc.clone(), },);
+	}
+}
+
+/// raml type name. enums are told apart from their base type since they restrict values
+fn type_label(raml_type: &RamlType,) -> String {
+	match raml_type {
+		RamlType::Enum(_, base,) => format!("enum of {}", base.to_string()),
+		raml_type => raml_type.to_string(),
+	}
+}
+
+/// reads `.object` metadata or a generated `.raml` library. libraries name no object, so the
+/// file stem is taken. `flat` libraries are rejected as they tell no field is required
+pub fn read_metadata_stream(path: impl AsRef<Path,>,) -> Rslt<RamlMetadataStream,> {
+	let path = path.as_ref();
+	if path.extension().is_none_or(|ext| ext != "raml",) {
+		return create_raml_metadata_stream(path,);
+	}
+
+	let library = read_raml_library(path,)?;
+	if library.object_types().is_empty() {
+		bail!(
+			"{} has no object types to tell required fields. read the `.object` file or a library \
+			 generated with `mode = request_response` or `crud`",
+			path.display()
+		)
+	}
+	let object_name = path.file_stem().unwrap_or_default().to_string_lossy();
+	Ok(library.to_metadata_stream()?.with_object_name(object_name,),)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::GenerationMode;
+	use crate::raml::render_raml;
+	use std::path::PathBuf;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	fn edit(
+		stream: &RamlMetadataStream,
+		name: &str,
+		f: impl FnOnce(&mut RamlTypesMetadata,),
+	) -> RamlMetadataStream {
+		let mut fields = stream.fields().to_vec();
+		f(fields.iter_mut().find(|field| field.name == name,).unwrap(),);
+		RamlMetadataStream::from_fields(fields, stream.object_name(),)
+	}
+
+	#[test]
+	fn test_no_changes() -> Rslt<(),> {
+		let stream = read_metadata_stream(SAMPLE_PATH,)?;
+		let diff = MetadataDiff::new(&stream, &stream,);
+		assert!(diff.changes.is_empty());
+		assert_eq!(diff.to_text(), "Sample__c: no changes\n");
+		Ok((),)
+	}
+
+	#[test]
+	fn test_classify_changes() -> Rslt<(),> {
+		let old = read_metadata_stream(SAMPLE_PATH,)?;
+		let new = edit(&old, "AccessCode__c", |field| field.max_length = Some(10,),);
+		let new = edit(&new, "StartDate__c", |field| field.required = true,);
+		let new = edit(&new, "Agreement__c", |field| {
+			if let RamlType::Enum(values, _,) = &mut field.type_on_raml {
+				values.remove(0,);
+				values.push("99：その他".to_string(),);
+			}
+		},);
+		let new = edit(&new, "UnitPrice__c", |field| field.desc = "単価（税抜）".to_string(),);
+		let new = new.filter(|field| field.name != "IsSigned__c",);
+
+		let diff = MetadataDiff::new(&old, &new,);
+		let summary: Vec<_,> = diff
+			.changes
+			.iter()
+			.map(|change| (change.field.as_str(), change.kind_name(), change.is_breaking(),),)
+			.collect();
+		assert_eq!(summary, [
+			("AccessCode__c", "maxLengthChanged", true),
+			("Agreement__c", "enumValuesRemoved", true),
+			("Agreement__c", "enumValuesAdded", false),
+			("UnitPrice__c", "descriptionChanged", false),
+			("IsSigned__c", "removed", true),
+			("StartDate__c", "requiredChanged", true),
+		]);
+		assert!(diff.has_breaking_change());
+
+		let text = diff.to_text();
+		let first = "Sample__c:\nBREAKING     AccessCode__c: maxLength changed 18 -> 10\n";
+		assert!(text.starts_with(first));
+		let last = "non-breaking UnitPrice__c: description changed \"単価\" -> \"単価（税抜）\"\n";
+		assert!(text.ends_with(last));
+
+		let json = diff.to_json();
+		assert_eq!(json["object"], "Sample__c");
+		assert_eq!(json["breaking"], true);
+		assert_eq!(json["changes"][1]["values"], json!(["89：一般"]));
+		Ok((),)
+	}
+
+	#[test]
+	fn test_added_fields() -> Rslt<(),> {
+		let new = read_metadata_stream(SAMPLE_PATH,)?;
+		let old = new
+			.clone()
+			.filter(|field| !matches!(field.name.as_str(), "Quantity__c" | "UnitPrice__c"),);
+
+		let diff = MetadataDiff::new(&old, &new,);
+		let breaking: Vec<_,> = diff.changes.iter().map(FieldChange::is_breaking,).collect();
+		assert_eq!(breaking, [true, false]);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_diff_rendered_libraries() -> Rslt<(),> {
+		let old = read_metadata_stream(SAMPLE_PATH,)?;
+		let new = edit(&old, "AccessCode__c", |field| field.max_length = Some(10,),);
+		let new = edit(&new, "UnitPrice__c", |field| field.required = true,);
+
+		let dir = std::env::temp_dir().join(format!("xraml_diff_{}", std::process::id()),);
+		std::fs::create_dir_all(&dir,)?;
+		let write = |name: &str, stream: &RamlMetadataStream, mode| -> Rslt<PathBuf,> {
+			let path = dir.join(name,);
+			std::fs::write(&path, render_raml(stream, mode,)?,)?;
+			Ok(path,)
+		};
+		let old_path = write("old.raml", &old, GenerationMode::Crud,)?;
+		let new_path = write("new.raml", &new, GenerationMode::Crud,)?;
+		let flat_path = write("flat.raml", &new, GenerationMode::Flat,)?;
+
+		let (old, new,) = (read_metadata_stream(&old_path,)?, read_metadata_stream(&new_path,)?,);
+		let diff = MetadataDiff::new(&old, &new,);
+		let flat = read_metadata_stream(&flat_path,);
+		std::fs::remove_dir_all(&dir,)?;
+
+		assert_eq!(diff.to_text(), "\
+new:
+BREAKING     AccessCode__c: maxLength changed 18 -> 10
+BREAKING     UnitPrice__c: became required
+");
+		assert!(flat.unwrap_err().to_string().contains("has no object types"));
+		Ok((),)
+	}
+}
@@ -0,0 +1,378 @@
+use crate::example_record::ExampleRecords;
+use crate::example_record::random_value;
+use crate::payload::PayloadMode;
+use crate::payload::validate_payload;
+use crate::raml::RamlMetadataStream;
+use crate::rng::Rng;
+use anyhow::Result as Rslt;
+use anyhow::anyhow;
+use anyhow::bail;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Read;
+use std::io::Write;
+use std::net::TcpListener;
+use std::net::TcpStream;
+use std::net::ToSocketAddrs;
+
+/// request bodies larger than this are answered with 413 without reading them
+const MAX_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq,)]
+pub struct MockResponse {
+	pub status: u16,
+	pub body:   Option<Value,>,
+}
+
+impl MockResponse {
+	fn new(status: u16, body: Value,) -> Self {
+		Self { status, body: Some(body,), }
+	}
+
+	fn no_content() -> Self {
+		Self { status: 204, body: None, }
+	}
+
+	/// salesforce rest api error body
+	fn error(status: u16, error_code: &str, message: &str,) -> Self {
+		Self::new(status, json!([{ "message": message, "errorCode": error_code, "fields": [] }]),)
+	}
+}
+
+struct MockObject {
+	data:   RamlMetadataStream,
+	/// every field, read-only ones included. served for any id
+	record: Map<String, Value,>,
+}
+
+/// stateless stand-in for the sobject rest api. answers
+/// - `POST .../sobjects/Foo__c` with a new id if the body is a valid record
+/// - `GET .../sobjects/Foo__c/{id}` with an example record
+/// - `PATCH .../sobjects/Foo__c/{id}` after validating the body as update
+/// - `DELETE .../sobjects/Foo__c/{id}`
+/// - `GET .../sobjects/Foo__c` with a short describe
+///
+/// any prefix such as `/services/data/v60.0` is accepted
+pub struct MockServer {
+	objects: Vec<MockObject,>,
+	rng:     Rng,
+}
+
+impl MockServer {
+	/// `properties` as in [`ExampleRecords::new`]
+	pub fn new(
+		objects: Vec<RamlMetadataStream,>,
+		properties: &[(String, String,)],
+		seed: u64,
+	) -> Rslt<Self,> {
+		let mut rng = Rng::new(seed,);
+		let objects = objects
+			.into_iter()
+			.map(|data| {
+				let mut record = ExampleRecords::new(&data, properties, &mut rng,)?.full;
+				for metadata in data.fields().iter().filter(|metadata| metadata.read_only,) {
+					record.insert(metadata.name.clone(), random_value(metadata, &mut rng,),);
+				}
+				Ok(MockObject { data, record, },)
+			},)
+			.collect::<Rslt<_,>>()?;
+		Ok(Self { objects, rng, },)
+	}
+
+	/// serves until the process ends. requests are handled one at a time
+	pub fn serve(&mut self, addr: impl ToSocketAddrs,) -> Rslt<(),> {
+		let listener = TcpListener::bind(addr,)?;
+		println!("listening on http://{}", listener.local_addr()?);
+		for stream in listener.incoming() {
+			// a failed accept, e.g. an aborted connection or too many open files, is not fatal
+			let stream = match stream {
+				Ok(stream,) => stream,
+				Err(e,) => {
+					eprintln!("{e}");
+					continue;
+				},
+			};
+			if let Err(e,) = self.serve_connection(stream,) {
+				eprintln!("{e}");
+			}
+		}
+		Ok((),)
+	}
+
+	pub fn serve_connection(&mut self, mut stream: TcpStream,) -> Rslt<(),> {
+		let (method, path, body,) = read_request(&mut stream,)?;
+		let response = match body {
+			Ok(body,) => self.handle(&method, &path, &body,),
+			Err(response,) => response,
+		};
+		println!("{method} {path} {}", response.status);
+		write_response(&mut stream, &response,)
+	}
+
+	pub fn handle(&mut self, method: &str, path: &str, body: &str,) -> MockResponse {
+		let path = path.split('?',).next().unwrap_or_default();
+		let Some((_, route,),) = path.split_once("/sobjects/",) else {
+			return MockResponse::error(404, "NOT_FOUND", "The requested resource does not exist",);
+		};
+		let (object_name, id,) = match route.trim_end_matches('/',).split_once('/',) {
+			Some((object_name, id,),) => (object_name, Some(id,),),
+			None => (route.trim_end_matches('/',), None,),
+		};
+		let Some(object,) = self.objects.iter().find(|o| o.data.object_name() == object_name,)
+		else {
+			let message = format!("sObject type '{object_name}' is not supported.");
+			return MockResponse::error(404, "NOT_FOUND", &message,);
+		};
+
+		match (method, id,) {
+			("GET", None,) => {
+				let fields: Vec<_,> = object
+					.data
+					.fields()
+					.iter()
+					.map(|metadata| json!({ "name": metadata.name, "label": metadata.desc }),)
+					.collect();
+				MockResponse::new(200, json!({ "name": object_name, "fields": fields }),)
+			},
+			("GET", Some(id,),) => {
+				let mut record = Map::new();
+				let url = format!("/sobjects/{object_name}/{id}");
+				record.insert("attributes".into(), json!({ "type": object_name, "url": url }),);
+				record.extend(object.record.clone(),);
+				// the example record has an `Id` of its own when the stream has the system field
+				record.insert("Id".into(), json!(id),);
+				MockResponse::new(200, Value::Object(record,),)
+			},
+			("POST", None,) => match validate_body(object, body, PayloadMode::Create,) {
+				Ok((),) => {
+					let id = format!("a00{}", self.rng.alphanumeric(15,));
+					MockResponse::new(201, json!({ "id": id, "success": true, "errors": [] }),)
+				},
+				Err(response,) => response,
+			},
+			("PATCH", Some(_,),) => match validate_body(object, body, PayloadMode::Update,) {
+				Ok((),) => MockResponse::no_content(),
+				Err(response,) => response,
+			},
+			("DELETE", Some(_,),) => MockResponse::no_content(),
+			_ => {
+				let message = format!("HTTP Method '{method}' not allowed");
+				MockResponse::error(405, "METHOD_NOT_ALLOWED", &message,)
+			},
+		}
+	}
+}
+
+fn validate_body(object: &MockObject, body: &str, mode: PayloadMode,) -> Result<(), MockResponse,> {
+	let payload: Value = serde_json::from_str(body,)
+		.map_err(|e| MockResponse::error(400, "JSON_PARSER_ERROR", &e.to_string(),),)?;
+	let violations = validate_payload(&object.data, &payload, mode,);
+	if violations.is_empty() {
+		return Ok((),);
+	}
+	let errors = violations.iter().map(|violation| violation.to_json(),).collect();
+	Err(MockResponse::new(400, Value::Array(errors,),),)
+}
+
+/// method, path and body of an http/1.1 request. a body that cannot be read comes as the
+/// response to answer instead
+fn read_request(
+	stream: &mut TcpStream,
+) -> Rslt<(String, String, Result<String, MockResponse,>,),> {
+	let mut reader = BufReader::new(stream,);
+	let mut request_line = String::new();
+	reader.read_line(&mut request_line,)?;
+	let mut parts = request_line.split_whitespace();
+	let (Some(method,), Some(path,),) = (parts.next(), parts.next(),) else {
+		bail!("malformed request line `{}`", request_line.trim_end())
+	};
+
+	let mut content_length = Ok(0,);
+	let mut chunked = false;
+	loop {
+		let mut header = String::new();
+		reader.read_line(&mut header,)?;
+		let header = header.trim_end();
+		if header.is_empty() {
+			break;
+		}
+		let Some((name, value,),) = header.split_once(':',) else { continue };
+		if name.eq_ignore_ascii_case("content-length",) {
+			let value = value.trim();
+			content_length = value.parse::<usize>().map_err(|_| value.to_string(),);
+		} else if name.eq_ignore_ascii_case("transfer-encoding",) {
+			chunked = value.to_ascii_lowercase().contains("chunked",);
+		}
+	}
+
+	let too_large = || {
+		let message = format!("request body exceeds {MAX_BODY_SIZE} bytes");
+		MockResponse::error(413, "REQUEST_ENTITY_TOO_LARGE", &message,)
+	};
+	let body = match content_length {
+		_ if chunked => read_chunked_body(&mut reader,)?.ok_or_else(too_large,),
+		Err(value,) => {
+			let message = format!("malformed Content-Length `{value}`");
+			Err(MockResponse::error(400, "MALFORMED_REQUEST", &message,),)
+		},
+		Ok(content_length,) if content_length > MAX_BODY_SIZE => Err(too_large(),),
+		Ok(content_length,) => {
+			let mut body = vec![0; content_length];
+			reader.read_exact(&mut body,)?;
+			Ok(body,)
+		},
+	};
+	let body = match body {
+		Ok(body,) => {
+			Ok(String::from_utf8(body,).map_err(|_| anyhow!("request body is not utf-8"),)?,)
+		},
+		Err(response,) => Err(response,),
+	};
+	Ok((method.to_string(), path.to_string(), body,),)
+}
+
+/// `Transfer-Encoding: chunked` body, as mule http requesters stream them. `None` once it grows
+/// beyond [`MAX_BODY_SIZE`]
+fn read_chunked_body(reader: &mut impl BufRead,) -> Rslt<Option<Vec<u8,>,>,> {
+	let mut body = vec![];
+	loop {
+		let mut size_line = String::new();
+		reader.read_line(&mut size_line,)?;
+		// chunk extensions follow `;`
+		let size = size_line.split(';',).next().unwrap_or_default().trim();
+		let size = usize::from_str_radix(size, 16,)
+			.map_err(|_| anyhow!("malformed chunk size `{}`", size_line.trim_end()),)?;
+		if size == 0 {
+			break;
+		}
+		if body.len() + size > MAX_BODY_SIZE {
+			return Ok(None,);
+		}
+
+		let start = body.len();
+		body.resize(start + size, 0,);
+		reader.read_exact(&mut body[start..],)?;
+		let mut line_end = String::new();
+		reader.read_line(&mut line_end,)?;
+	}
+
+	// trailers end with an empty line
+	loop {
+		let mut trailer = String::new();
+		if reader.read_line(&mut trailer,)? == 0 || trailer.trim_end().is_empty() {
+			break;
+		}
+	}
+	Ok(Some(body,),)
+}
+
+fn write_response(stream: &mut TcpStream, response: &MockResponse,) -> Rslt<(),> {
+	let reason = match response.status {
+		200 => "OK",
+		201 => "Created",
+		204 => "No Content",
+		400 => "Bad Request",
+		404 => "Not Found",
+		413 => "Payload Too Large",
+		_ => "Method Not Allowed",
+	};
+	let body = response.body.as_ref().map(|body| body.to_string(),).unwrap_or_default();
+	write!(stream, "HTTP/1.1 {} {reason}\r\n", response.status)?;
+	write!(stream, "Content-Type: application/json\r\nContent-Length: {}\r\n", body.len())?;
+	write!(stream, "Connection: close\r\n\r\n{body}")?;
+	stream.flush()?;
+	Ok((),)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	fn server() -> Rslt<MockServer,> {
+		MockServer::new(vec![create_raml_metadata_stream(SAMPLE_PATH,)?], &[], 0,)
+	}
+
+	#[test]
+	fn test_handle() -> Rslt<(),> {
+		let mut server = server()?;
+		let base = "/services/data/v60.0/sobjects/Sample__c";
+
+		let created = server.handle("POST", base, r#"{"Agreement__c":"89：一般","Quantity__c":1}"#,);
+		assert_eq!(created.status, 201);
+		assert_eq!(created.body.unwrap()["id"].as_str().unwrap().len(), 18);
+
+		let rejected = server.handle("POST", base, r#"{"Quantity__c":"1"}"#,);
+		assert_eq!(rejected.status, 400);
+		let errors = rejected.body.unwrap();
+		let codes: Vec<_,> = errors.as_array().unwrap().iter().map(|e| &e["errorCode"],).collect();
+		assert_eq!(codes, ["JSON_PARSER_ERROR", "REQUIRED_FIELD_MISSING"]);
+
+		let record = server.handle("GET", &format!("{base}/a00000000000000AAA"), "",);
+		let record = record.body.unwrap();
+		assert_eq!(record["Id"], "a00000000000000AAA");
+		assert!(record["TotalAmount__c"].is_number());
+
+		let patched = server.handle("PATCH", &format!("{base}/x"), r#"{"UnitPrice__c":1}"#,);
+		assert_eq!(patched.status, 204);
+		assert_eq!(server.handle("PATCH", &format!("{base}/x"), "{",).status, 400);
+		assert_eq!(server.handle("DELETE", &format!("{base}/x"), "",).status, 204);
+		assert_eq!(server.handle("PUT", base, "",).status, 405);
+		assert_eq!(server.handle("GET", "/sobjects/Other__c", "",).status, 404);
+		Ok((),)
+	}
+
+	/// response of the server to `request` sent over a real connection
+	fn exchange(request: String,) -> Rslt<String,> {
+		let listener = TcpListener::bind("127.0.0.1:0",)?;
+		let addr = listener.local_addr()?;
+		let client = std::thread::spawn(move || -> Rslt<String,> {
+			let mut stream = TcpStream::connect(addr,)?;
+			stream.write_all(request.as_bytes(),)?;
+			let mut response = String::new();
+			stream.read_to_string(&mut response,)?;
+			Ok(response,)
+		},);
+
+		server()?.serve_connection(listener.accept()?.0,)?;
+		client.join().unwrap()
+	}
+
+	#[test]
+	fn test_serve_connection() -> Rslt<(),> {
+		let body = r#"{"Quantity__c":null}"#;
+		let head = "PATCH /sobjects/Sample__c/x HTTP/1.1\r\n";
+		let response = exchange(format!("{head}Content-Length: {}\r\n\r\n{body}", body.len()),)?;
+		assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+		let error = r#""errorCode":"REQUIRED_FIELD_MISSING","fields":["Quantity__c"]"#;
+		assert!(response.contains(error));
+
+		let chunks = "7;x=y\r\n{\"UnitP\r\nd\r\nrice__c\":1.5}\r\n0\r\n\r\n";
+		let response = exchange(format!("{head}Transfer-Encoding: chunked\r\n\r\n{chunks}"),)?;
+		assert!(response.starts_with("HTTP/1.1 204 No Content\r\n"), "{response}");
+
+		let too_large = format!("Content-Length: {}\r\n\r\n", MAX_BODY_SIZE + 1);
+		let response = exchange(format!("{head}{too_large}"),)?;
+		assert!(response.starts_with("HTTP/1.1 413 Payload Too Large\r\n"));
+		assert!(response.contains("REQUEST_ENTITY_TOO_LARGE"));
+
+		let response = exchange(format!("{head}Content-Length: ten\r\n\r\n{body}"),)?;
+		assert!(response.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+		assert!(response.contains("malformed Content-Length `ten`"));
+		Ok((),)
+	}
+
+	#[test]
+	fn test_record_id_from_url() -> Rslt<(),> {
+		let data = create_raml_metadata_stream(SAMPLE_PATH,)?.with_system_fields(&["Id"],)?;
+		let mut server = MockServer::new(vec![data], &[], 0,)?;
+		let record = server.handle("GET", "/sobjects/Sample__c/a00000000000001AAA", "",);
+		assert_eq!(record.body.unwrap()["Id"], "a00000000000001AAA");
+		Ok((),)
+	}
+}
@@ -0,0 +1,264 @@
+use crate::json_schema::properties_schema;
+use crate::raml::RamlMetadataStream;
+use crate::raml::type_base_name;
+use anyhow::Result as Rslt;
+use serde_json::Map;
+use serde_json::Value;
+use serde_json::json;
+use std::path::Path;
+
+const OPENAPI_VERSION: &str = "3.1.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default,)]
+pub enum OpenApiFormat {
+	#[default]
+	Yaml,
+	Json,
+}
+
+impl RamlMetadataStream {
+	pub fn create_openapi_file(
+		self,
+		filename: String,
+		format: OpenApiFormat,
+		crud_paths: bool,
+	) -> Rslt<(),> {
+		create_openapi_file(self, format!("data/{filename}"), format, crud_paths,)
+	}
+}
+
+pub fn create_openapi_file(
+	data: RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	format: OpenApiFormat,
+	crud_paths: bool,
+) -> Rslt<(),> {
+	let contents = render_openapi(&data, format, crud_paths,)?;
+	std::fs::write(filename, contents,)?;
+	Ok((),)
+}
+
+pub fn render_openapi(
+	data: &RamlMetadataStream,
+	format: OpenApiFormat,
+	crud_paths: bool,
+) -> Rslt<String,> {
+	let document = openapi_document(data, crud_paths,)?;
+	let rslt = match format {
+		OpenApiFormat::Yaml => to_yaml(&document,),
+		OpenApiFormat::Json => serde_json::to_string_pretty(&document,)?,
+	};
+	Ok(rslt,)
+}
+
+/// `components.schemas` holds `Foo` (every field), `FooCreate` (writable fields, required ones
+/// are required) and `FooUpdate` (writable fields, all optional), same as
+/// `GenerationMode::Crud` of the raml output
+pub fn openapi_document(data: &RamlMetadataStream, crud_paths: bool,) -> Rslt<Value,> {
+	let base = type_base_name(data.object_name(),)?;
+	let fields = data.fields();
+	let writable = fields.iter().filter(|metadata| !metadata.read_only,);
+
+	let mut schemas = Map::new();
+	schemas.insert(
+		base.to_string(),
+		properties_schema(fields.iter().map(|metadata| (metadata, metadata.required,),),),
+	);
+	schemas.insert(
+		format!("{base}Create"),
+		properties_schema(writable.clone().map(|metadata| (metadata, metadata.required,),),),
+	);
+	schemas.insert(
+		format!("{base}Update"),
+		properties_schema(writable.map(|metadata| (metadata, false,),),),
+	);
+
+	let mut document = json!({
+		"openapi": OPENAPI_VERSION,
+		"info": { "title": data.object_name(), "version": "1.0.0" },
+	});
+	if crud_paths {
+		document["paths"] = sobject_paths(data.object_name(), base,);
+		schemas.insert("SaveResult".to_string(), save_result_schema(),);
+	}
+	document["components"] = json!({ "schemas": schemas });
+	Ok(document,)
+}
+
+/// rest api endpoints of an sobject. request and response bodies refer to `components.schemas`
+fn sobject_paths(object_name: &str, base: &str,) -> Value {
+	let schema_ref = |name: &str| json!({ "$ref": format!("#/components/schemas/{name}") });
+	let content = |name: &str| json!({ "application/json": { "schema": schema_ref(name) } });
+	let id_parameter = json!({
+		"name": "id",
+		"in": "path",
+		"required": true,
+		"schema": { "type": "string", "maxLength": 18 },
+	});
+
+	json!({
+		format!("/sobjects/{object_name}"): {
+			"post": {
+				"operationId": format!("create{base}"),
+				"requestBody": { "required": true, "content": content(&format!("{base}Create")) },
+				"responses": {
+					"201": { "description": "created", "content": content("SaveResult") },
+				},
+			},
+		},
+		format!("/sobjects/{object_name}/{{id}}"): {
+			"parameters": [id_parameter],
+			"get": {
+				"operationId": format!("get{base}"),
+				"responses": {
+					"200": { "description": "record", "content": content(base) },
+				},
+			},
+			"patch": {
+				"operationId": format!("update{base}"),
+				"requestBody": { "required": true, "content": content(&format!("{base}Update")) },
+				"responses": { "204": { "description": "updated" } },
+			},
+			"delete": {
+				"operationId": format!("delete{base}"),
+				"responses": { "204": { "description": "deleted" } },
+			},
+		},
+	})
+}
+
+/// response body of record creation
+fn save_result_schema() -> Value {
+	json!({
+		"type": "object",
+		"properties": {
+			"id": { "type": "string", "maxLength": 18 },
+			"success": { "type": "boolean" },
+			"errors": { "type": "array", "items": {} },
+		},
+		"required": ["id", "success", "errors"],
+	})
+}
+
+/// block style yaml. strings are always double quoted, which accepts json escapes
+pub fn to_yaml(value: &Value,) -> String {
+	let mut lines = vec![];
+	yaml_lines(value, 0, &mut lines,);
+	lines.join("\n",) + "\n"
+}
+
+fn yaml_lines(value: &Value, indent: usize, lines: &mut Vec<String,>,) {
+	let pad = " ".repeat(indent,);
+	match value {
+		Value::Object(map,) if !map.is_empty() => {
+			for (key, value,) in map {
+				let key = yaml_key(key,);
+				if is_nested(value,) {
+					lines.push(format!("{pad}{key}:"),);
+					yaml_lines(value, indent + 2, lines,);
+				} else {
+					lines.push(format!("{pad}{key}: {}", yaml_scalar(value)),);
+				}
+			}
+		},
+		Value::Array(items,) if !items.is_empty() => {
+			for item in items {
+				if is_nested(item,) {
+					let first = lines.len();
+					yaml_lines(item, indent + 2, lines,);
+					lines[first].replace_range(..indent + 2, &format!("{pad}- "),);
+				} else {
+					lines.push(format!("{pad}- {}", yaml_scalar(item)),);
+				}
+			}
+		},
+		value => lines.push(format!("{pad}{}", yaml_scalar(value)),),
+	}
+}
+
+fn is_nested(value: &Value,) -> bool {
+	match value {
+		Value::Object(map,) => !map.is_empty(),
+		Value::Array(items,) => !items.is_empty(),
+		_ => false,
+	}
+}
+
+fn yaml_key(key: &str,) -> String {
+	let is_plain = key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_',)
+		&& key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_',);
+	if is_plain { key.to_string() } else { Value::from(key,).to_string() }
+}
+
+/// empty containers and scalars are valid in json flow style, which yaml accepts as is
+fn yaml_scalar(value: &Value,) -> String {
+	value.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_openapi_schemas() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let document = openapi_document(&raml_stream, false,)?;
+		let schemas = &document["components"]["schemas"];
+
+		assert_eq!(document["openapi"], "3.1.0");
+		assert!(document.get("paths").is_none());
+		assert_eq!(schemas["Sample"]["required"], json!(["Agreement__c", "Quantity__c"]));
+		assert_eq!(schemas["SampleCreate"]["required"], json!(["Agreement__c", "Quantity__c"]));
+		assert_eq!(schemas["SampleUpdate"]["required"], json!([]));
+		assert!(schemas["SampleUpdate"]["properties"].get("TotalAmount__c").is_none());
+		assert_eq!(schemas["Sample"]["properties"]["TotalAmount__c"]["readOnly"], true);
+		assert_eq!(schemas["Sample"]["properties"]["AccessCode__c"]["maxLength"], 18);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_openapi_paths() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let document = openapi_document(&raml_stream, true,)?;
+		let paths = &document["paths"];
+
+		let post = &paths["/sobjects/Sample__c"]["post"];
+		assert_eq!(
+			post["requestBody"]["content"]["application/json"]["schema"]["$ref"],
+			"#/components/schemas/SampleCreate"
+		);
+		let get = &paths["/sobjects/Sample__c/{id}"]["get"];
+		assert_eq!(
+			get["responses"]["200"]["content"]["application/json"]["schema"]["$ref"],
+			"#/components/schemas/Sample"
+		);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_to_yaml() {
+		let value = json!({
+			"openapi": "3.1.0",
+			"$ref": "#/x",
+			"required": ["Agreement__c"],
+			"parameters": [{ "name": "id", "in": "path" }],
+			"empty": [],
+			"nested": { "maxLength": 18 },
+		});
+		let answer = r##"openapi: "3.1.0"
+"$ref": "#/x"
+required:
+  - "Agreement__c"
+parameters:
+  - name: "id"
+    in: "path"
+empty: []
+nested:
+  maxLength: 18
+"##;
+		assert_eq!(to_yaml(&value), answer);
+	}
+}
@@ -0,0 +1,280 @@
+use crate::example::check_type;
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use anyhow::Result as Rslt;
+use anyhow::bail;
+use serde_json::Value;
+use serde_json::json;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// operation a payload is sent for. required fields are only enforced on create
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default,)]
+pub enum PayloadMode {
+	#[default]
+	Create,
+	/// fields left out keep their value, so only clearing a required field is an error
+	Update,
+}
+
+impl FromStr for PayloadMode {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str,) -> Rslt<Self,> {
+		match s {
+			"create" => Ok(Self::Create,),
+			"update" => Ok(Self::Update,),
+			_ => bail!("unknown mode `{s}`. expect create or update"),
+		}
+	}
+}
+
+/// one reason salesforce would reject the payload
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct PayloadViolation {
+	/// json pointer to the offending value, `""` for the payload itself
+	pub pointer:    String,
+	pub field:      String,
+	/// field label, empty for fields the object does not have
+	pub label:      String,
+	/// salesforce error code such as `REQUIRED_FIELD_MISSING`
+	pub error_code: &'static str,
+	pub message:    String,
+}
+
+impl PayloadViolation {
+	/// entry of a salesforce rest api error response
+	pub fn to_json(&self,) -> Value {
+		let fields = if self.field.is_empty() { vec![] } else { vec![self.field.clone()] };
+		json!({ "message": self.message, "errorCode": self.error_code, "fields": fields })
+	}
+}
+
+impl Display for PayloadViolation {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_,>,) -> std::fmt::Result {
+		let pointer = if self.pointer.is_empty() { "/" } else { &self.pointer };
+		match self.label.as_str() {
+			"" => write!(f, "{pointer}: {} ({})", self.message, self.error_code),
+			label => write!(f, "{pointer} ({label}): {} ({})", self.message, self.error_code),
+		}
+	}
+}
+
+/// checks a record body against types, lengths, picklist values, precision and required flags
+pub fn validate_payload(
+	data: &RamlMetadataStream,
+	payload: &Value,
+	mode: PayloadMode,
+) -> Vec<PayloadViolation,> {
+	let Value::Object(record,) = payload else {
+		return vec![PayloadViolation {
+			pointer:    String::new(),
+			field:      String::new(),
+			label:      String::new(),
+			error_code: "JSON_PARSER_ERROR",
+			message:    "payload has to be a json object".to_string(),
+		}];
+	};
+
+	let mut violations = vec![];
+	for (name, value,) in record {
+		// salesforce accepts the type information of its own responses
+		if name == "attributes" {
+			continue;
+		}
+		match data.fields().iter().find(|metadata| metadata.name == *name,) {
+			Some(metadata,) => violations.extend(validate_value(metadata, value,),),
+			None => violations.push(PayloadViolation {
+				pointer:    pointer(name,),
+				field:      name.clone(),
+				label:      String::new(),
+				error_code: "INVALID_FIELD",
+				message:    format!("no such field on {}", data.object_name()),
+			},),
+		}
+	}
+
+	let writable = data.fields().iter().filter(|metadata| !metadata.read_only,);
+	for metadata in writable.filter(|metadata| metadata.required,) {
+		let missing = matches!(
+			(record.get(&metadata.name,), mode,),
+			(Some(Value::Null,), _,) | (None, PayloadMode::Create,)
+		);
+		if missing {
+			let message = "required field is missing";
+			violations.push(violation(metadata, "REQUIRED_FIELD_MISSING", message,),);
+		}
+	}
+	violations
+}
+
+/// same as [`validate_payload`], also taking an array of records. pointers then start with the
+/// index of the record
+pub fn validate_payload_document(
+	data: &RamlMetadataStream,
+	document: &Value,
+	mode: PayloadMode,
+) -> Vec<PayloadViolation,> {
+	let Value::Array(records,) = document else {
+		return validate_payload(data, document, mode,);
+	};
+	records
+		.iter()
+		.enumerate()
+		.flat_map(|(i, record,)| {
+			validate_payload(data, record, mode,).into_iter().map(move |mut violation| {
+				violation.pointer = format!("/{i}{}", violation.pointer);
+				violation
+			},)
+		},)
+		.collect()
+}
+
+fn validate_value(metadata: &RamlTypesMetadata, value: &Value,) -> Option<PayloadViolation,> {
+	if metadata.read_only {
+		let message = "field is read-only";
+		return Some(violation(metadata, "INVALID_FIELD_FOR_INSERT_UPDATE", message,),);
+	}
+
+	let (scalar, is_quoted,) = match value {
+		// required fields are checked separately
+		Value::Null => return None,
+		Value::String(text,) => (text.clone(), true,),
+		Value::Number(number,) => (number.to_string(), false,),
+		Value::Bool(flag,) => (flag.to_string(), false,),
+		Value::Array(_,) | Value::Object(_,) => {
+			let message = format!("expect {}, found a json structure", type_name(metadata,));
+			return Some(violation(metadata, "JSON_PARSER_ERROR", &message,),);
+		},
+	};
+
+	if let Err(e,) = check_type(&metadata.type_on_raml, &scalar, is_quoted,) {
+		let error_code = match &metadata.type_on_raml {
+			RamlType::Enum(values, _,) if is_quoted && !values.contains(&scalar,) => {
+				"INVALID_OR_NULL_FOR_RESTRICTED_PICKLIST"
+			},
+			_ => "JSON_PARSER_ERROR",
+		};
+		return Some(violation(metadata, error_code, &format!("{value} {e}"),),);
+	}
+
+	if let Some(max_length,) = metadata.max_length
+		&& scalar.chars().count() > max_length
+	{
+		let message = format!("longer than maxLength {max_length}");
+		return Some(violation(metadata, "STRING_TOO_LONG", &message,),);
+	}
+
+	if let (Some(precision,), Some(number,),) = (metadata.precision, value.as_f64(),) {
+		let integer_digits = precision.saturating_sub(metadata.scale.unwrap_or_default(),);
+		if number.abs() >= 10f64.powi(integer_digits as i32,) {
+			let message = format!("{number} has more than {integer_digits} integer digits");
+			return Some(violation(metadata, "NUMBER_OUTSIDE_VALID_RANGE", &message,),);
+		}
+	}
+	None
+}
+
+fn violation(
+	metadata: &RamlTypesMetadata,
+	error_code: &'static str,
+	message: &str,
+) -> PayloadViolation {
+	PayloadViolation {
+		pointer: pointer(&metadata.name,),
+		field: metadata.name.clone(),
+		label: metadata.desc.lines().next().unwrap_or_default().to_string(),
+		error_code,
+		message: message.to_string(),
+	}
+}
+
+fn type_name(metadata: &RamlTypesMetadata,) -> String {
+	match &metadata.type_on_raml {
+		RamlType::Enum(_, base,) => base.to_string(),
+		raml_type => raml_type.to_string(),
+	}
+}
+
+/// rfc 6901 pointer to a top level member
+fn pointer(name: &str,) -> String {
+	format!("/{}", name.replace('~', "~0",).replace('/', "~1",))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	fn codes(violations: &[PayloadViolation],) -> Vec<(&str, &str,),> {
+		violations.iter().map(|v| (v.pointer.as_str(), v.error_code,),).collect()
+	}
+
+	#[test]
+	fn test_validate_payload() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let valid = json!({ "Agreement__c": "89：一般", "Quantity__c": 12, "IsSigned__c": null });
+		assert_eq!(validate_payload(&raml_stream, &valid, PayloadMode::Create,), []);
+
+		let invalid = json!({
+			"attributes": { "type": "Sample__c" },
+			"AccessCode__c": "X".repeat(19),
+			"Agreement__c": "99：その他",
+			"UnitPrice__c": 123456789.5,
+			"StartDate__c": "2024/04/01",
+			"TotalAmount__c": 1,
+			"Unknown__c": true,
+		});
+		let violations = validate_payload(&raml_stream, &invalid, PayloadMode::Create,);
+		assert_eq!(codes(&violations), [
+			("/AccessCode__c", "STRING_TOO_LONG"),
+			("/Agreement__c", "INVALID_OR_NULL_FOR_RESTRICTED_PICKLIST"),
+			("/UnitPrice__c", "NUMBER_OUTSIDE_VALID_RANGE"),
+			("/StartDate__c", "JSON_PARSER_ERROR"),
+			("/TotalAmount__c", "INVALID_FIELD_FOR_INSERT_UPDATE"),
+			("/Unknown__c", "INVALID_FIELD"),
+			("/Quantity__c", "REQUIRED_FIELD_MISSING"),
+		]);
+		assert_eq!(
+			violations[0].to_string(),
+			"/AccessCode__c (電子契約-アクセスコード): longer than maxLength 18 (STRING_TOO_LONG)"
+		);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_update_mode() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let patch = json!({ "UnitPrice__c": 10.5 });
+		assert_eq!(validate_payload(&raml_stream, &patch, PayloadMode::Update,), []);
+
+		let clear = json!({ "Quantity__c": null });
+		let violations = validate_payload(&raml_stream, &clear, PayloadMode::Update,);
+		assert_eq!(codes(&violations), [("/Quantity__c", "REQUIRED_FIELD_MISSING")]);
+
+		let violations = validate_payload(&raml_stream, &json!([]), PayloadMode::Update,);
+		let message = "/: payload has to be a json object (JSON_PARSER_ERROR)";
+		assert_eq!(violations[0].to_string(), message);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_validate_record_array() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let records = json!([
+			{ "Agreement__c": "89：一般", "Quantity__c": 12 },
+			{ "Agreement__c": "89：一般", "Quantity__c": 12345 },
+			"Quantity__c",
+		]);
+		let violations = validate_payload_document(&raml_stream, &records, PayloadMode::Create,);
+		assert_eq!(codes(&violations), [
+			("/1/Quantity__c", "NUMBER_OUTSIDE_VALID_RANGE"),
+			("/2", "JSON_PARSER_ERROR"),
+		]);
+		assert_eq!(violations[0].label, "数量");
+		Ok((),)
+	}
+}
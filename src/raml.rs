@@ -7,14 +7,63 @@ use std::collections::VecDeque;
 use std::path::Path;
 
 use crate::csv::CsvRows;
+use crate::description::DescriptionComposition;
+use crate::example::default_example;
+use crate::example::quote;
+use crate::field_filter::FieldFilter;
+use crate::system_field::SystemField;
+use crate::translation::FieldTranslation;
 const RAML_HEAD: &str = "#%RAML 1.0 Library\n\ntypes:";
+/// declaration [`RAML_HEAD`] gets when a field defaults to a formula
+const DEFAULT_FORMULA_DECLARATION: &str = "annotationTypes:\n  defaultFormula: string\n\ntypes:";
+
+/// annotation carrying a default salesforce computes, which raml `default` cannot express
+///
+/// ```yaml
+///   StartDate__c:
+///     (defaultFormula): "TODAY()"
+/// ```
+pub const DEFAULT_FORMULA_ANNOTATION: &str = "(defaultFormula)";
+
+/// how the fields of an object are laid out in the generated library
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default,)]
+pub enum GenerationMode {
+	/// one type per field
+	#[default]
+	Flat,
+	/// field types plus `FooRequest` (writable fields only) and `FooResponse` object types
+	RequestResponse,
+	/// field types plus `FooUpdate` (writable fields, all optional), `FooCreate` (inherits
+	/// `FooUpdate`, required fields are required) and `Foo` (inherits `FooCreate`, adds read-only
+	/// fields)
+	Crud,
+}
+
+impl std::str::FromStr for GenerationMode {
+	type Err = anyhow::Error;
+
+	fn from_str(s: &str,) -> Rslt<Self,> {
+		match s {
+			"flat" => Ok(Self::Flat,),
+			"request_response" => Ok(Self::RequestResponse,),
+			"crud" => Ok(Self::Crud,),
+			_ => bail!("unknown mode `{s}`. expect flat, request_response or crud"),
+		}
+	}
+}
 
-#[derive(Debug,)]
-pub struct RamlMetadataStream(Vec<RamlTypesMetadata,>,);
+/// metadata of each field, and api name of the object they belong to
+#[derive(Debug, Clone,)]
+pub struct RamlMetadataStream(Vec<RamlTypesMetadata,>, String,);
 
 impl RamlMetadataStream {
 	pub fn new(doc: &Document,) -> Rslt<Self,> {
 		let co = get_custom_object(doc,).unwrap();
+		let object_name = co
+			.children()
+			.find(|child| child.tag_name().name() == "fullName",)
+			.map(|node| get_text_of_node(&node,),)
+			.unwrap_or_default();
 		let valiant_list = enum_variant_list(&co,);
 		let body: Vec<_,> = co
 			.children()
@@ -25,7 +74,46 @@ impl RamlMetadataStream {
 			},)
 			.collect();
 
-		Ok(Self(body,),)
+		Ok(Self(body, object_name,),)
+	}
+
+	pub fn from_fields(fields: Vec<RamlTypesMetadata,>, object_name: impl Into<String,>,) -> Self {
+		Self(fields, object_name.into(),)
+	}
+
+	pub fn with_object_name(mut self, object_name: impl Into<String,>,) -> Self {
+		self.1 = object_name.into();
+		self
+	}
+
+	pub fn object_name(&self,) -> &str {
+		&self.1
+	}
+
+	pub fn fields(&self,) -> &[RamlTypesMetadata] {
+		&self.0
+	}
+
+	pub fn fields_mut(&mut self,) -> &mut [RamlTypesMetadata] {
+		&mut self.0
+	}
+
+	/// prepends standard fields listed in `names` (see [`crate::system_field::SYSTEM_FIELDS`]).
+	/// fields the object already declares are left as is
+	pub fn with_system_fields(mut self, names: &[impl AsRef<str,>],) -> Rslt<Self,> {
+		let mut injected = Vec::with_capacity(names.len(),);
+		for name in names {
+			let name = name.as_ref();
+			let system_field = SystemField::find(name,)
+				.ok_or(anyhow!("`{name}` is not in the catalogue of system fields"),)?;
+			if self.0.iter().all(|metadata| metadata.name != name,) {
+				injected.push(system_field.to_raml_metadata(),);
+			}
+		}
+
+		injected.append(&mut self.0,);
+		self.0 = injected;
+		Ok(self,)
 	}
 
 	pub fn filter(mut self, prediction: impl FnMut(&RamlTypesMetadata,) -> bool,) -> Self {
@@ -40,10 +128,18 @@ impl RamlMetadataStream {
 		},)
 	}
 
+	pub fn filter_fields(self, field_filter: &FieldFilter,) -> Self {
+		self.filter(|raml_types_metadata| field_filter.matches(raml_types_metadata,),)
+	}
+
 	pub fn create_raml_file(self, filename: String,) -> Rslt<(),> {
 		create_raml_file(self, format!("data/{filename}"),)
 	}
 
+	pub fn create_raml_file_with_mode(self, filename: String, mode: GenerationMode,) -> Rslt<(),> {
+		create_raml_file_with_mode(self, format!("data/{filename}"), mode,)
+	}
+
 	pub fn create_raml_file_minimal(self, row_names: Vec<String,>, filename: String,) -> Rslt<(),> {
 		let selfff = self.filter_required_rows(row_names,);
 		println!("types of {filename}: {}", selfff.0.len());
@@ -51,14 +147,36 @@ impl RamlMetadataStream {
 	}
 }
 
-#[derive(PartialEq, Eq, Debug,)]
+#[derive(PartialEq, Eq, Debug, Clone,)]
 pub struct RamlTypesMetadata {
-	pub name:         String,
-	pub type_on_raml: RamlType,
-	pub desc:         String,
-	pub example:      String,
-	pub max_length:   Option<usize,>,
-	pub required:     bool,
+	pub name:             String,
+	/// field type as declared in `.object`, e.g. `Text`, `Summary`, `EncryptedText`
+	pub sf_type:          String,
+	pub type_on_raml:     RamlType,
+	/// `<label>` of the field
+	pub desc:             String,
+	/// `<description>` of the field, if any
+	pub description:      Option<String,>,
+	/// `<inlineHelpText>` of the field, if any
+	pub help_text:        Option<String,>,
+	/// labels and help texts in other languages, see [`RamlMetadataStream::translated`]
+	pub translations:     Vec<FieldTranslation,>,
+	/// `description` facet of the raml type. `None` renders the default
+	/// [`DescriptionComposition`]
+	pub raml_description: Option<String,>,
+	pub example:          String,
+	/// what salesforce fills in when the field is omitted on insert
+	pub default_value:    Option<DefaultValue,>,
+	pub max_length:       Option<usize,>,
+	/// total digits of number fields
+	pub precision:        Option<usize,>,
+	/// digits after the decimal point of number fields
+	pub scale:            Option<usize,>,
+	/// objects a lookup or master-detail field points to. several for polymorphic lookups
+	pub reference_to:     Vec<String,>,
+	pub required:         bool,
+	/// formula, roll-up summary and auto-number fields. salesforce rejects writes to them
+	pub read_only:        bool,
 }
 
 impl RamlTypesMetadata {
@@ -68,17 +186,25 @@ impl RamlTypesMetadata {
 		}
 
 		let mut name = None;
-		let mut type_on_raml = None;
+		let mut sf_type = None;
 		let mut desc = None;
-		let mut example = "".to_string();
+		let mut description = None;
+		let mut help_text = None;
+		let mut default_value = None;
 		let mut max_length = None;
+		let mut precision = None;
+		let mut scale = None;
+		let mut reference_to = vec![];
 		let mut required = false;
+		let mut formula = None;
+		let mut formula_treat_blanks_as = None;
+		let mut return_type = None;
+		let mut summary_operation = None;
 
 		fields.children().for_each(|node| {
 			let tag_name = node.tag_name().name();
 			let text = get_text_of_node(&node,);
 
-			const SFID_LEN: usize = 18;
 			match tag_name {
 				"fullName" => {
 					name.replace(text,);
@@ -86,75 +212,146 @@ impl RamlTypesMetadata {
 				"label" => {
 					desc.replace(text,);
 				},
+				"description" => {
+					description.replace(text,);
+				},
+				"inlineHelpText" => {
+					help_text.replace(text,);
+				},
+				"defaultValue" => {
+					default_value.replace(text,);
+				},
 				"length" => {
 					max_length.replace(text.parse::<usize>().expect("failed to get length",),);
 				},
+				"precision" => {
+					precision.replace(text.parse::<usize>().expect("failed to get precision",),);
+				},
+				"scale" => {
+					scale.replace(text.parse::<usize>().expect("failed to get scale",),);
+				},
+				"referenceTo" => reference_to.push(text,),
 				"type" => {
-					let rt = match text.as_str() {
-						"Lookup" => {
-							max_length.replace(SFID_LEN,);
-							example = format!("\"{}\"", "X".repeat(SFID_LEN));
-							RamlType::String
-						},
-						// TODO: impl later
-						"Picklist" => RamlType::Enum(vec![], Box::new(RamlType::String,),),
-						"Number" => {
-							example = "0".to_string();
-							RamlType::Number
-						},
-						"Checkbox" => {
-							example = "true".to_string();
-							RamlType::Boolean
-						},
-						"Date" => RamlType::Date,
-						_a => {
-							example = "\"XXX\"".to_string();
-							// println!("{_a}");
-							RamlType::String
-						},
-					};
-					type_on_raml.replace(rt,);
+					sf_type.replace(text,);
 				},
 				"required" => {
 					if text.as_str() == "true" {
 						required = true;
 					}
 				},
+				"formula" => {
+					formula.replace(text,);
+				},
+				"formulaTreatBlanksAs" => {
+					formula_treat_blanks_as.replace(text,);
+				},
+				"returnType" => {
+					return_type.replace(text,);
+				},
+				"summaryOperation" => {
+					summary_operation.replace(text,);
+				},
 				// a => unimplemented!("parser for tag with name: `{a}`\n\nnode: {node:?}\n\n"),
 				_a => (), //println!("unimplemented tag parser: {a}"),
 			};
 		},);
 
-		let name = name.unwrap();
+		let name: String = name.unwrap();
+
+		// calculated fields carry their result type in `returnType` (or `type` for formulas),
+		// roll-up summaries only tell `Summary` unless they count rows
+		let result_type = match (sf_type.as_deref(), return_type.as_deref(),) {
+			(Some("Summary",), _,) if summary_operation.as_deref() == Some("count",) => "Number",
+			(_, Some(return_type,),) => return_type,
+			(Some("Summary",), None,) => "Number",
+			(Some(sf_type,), None,) => sf_type,
+			(None, None,) if formula_treat_blanks_as.as_deref() == Some("BlankAsZero",) => {
+				"Number"
+			},
+			(None, None,) => bail!("field `{name}` has no type"),
+		};
+
+		let read_only = formula.is_some()
+			|| summary_operation.is_some()
+			|| result_type == "AutoNumber";
+
+		let (type_on_raml, example,) = sf_type_to_raml(result_type, &mut max_length,);
+		let default_value = default_value
+			.map(|formula| DefaultValue::from_formula(&formula, &type_on_raml, max_length,),);
+		let sf_type = sf_type.as_deref().unwrap_or(result_type,).to_string();
 		// let type_on_raml = RamlType::Any;
-		let type_on_raml = type_on_raml.unwrap();
 		let desc = desc.unwrap();
 
-		Ok(Self { name, type_on_raml, desc, example, max_length, required, },)
+		Ok(Self {
+			name,
+			sf_type,
+			type_on_raml,
+			desc,
+			description,
+			help_text,
+			translations: vec![],
+			raml_description: None,
+			example,
+			default_value,
+			max_length,
+			precision,
+			scale,
+			reference_to,
+			required,
+			read_only,
+		},)
 	}
 
 	pub fn format_as_raml(&self,) -> String {
 		let mut lines = Vec::with_capacity(4,);
 		lines.push(format!("  {}:", self.name.clone()),);
 		lines.push(format!("type: {}", self.type_on_raml.to_string()),);
+		lines.push(format!("displayName: {}", quote(&self.desc,)),);
 		lines.push(format!("description: |"),);
-		lines.push(format!("  {}", self.desc.clone()),);
+		let description = self
+			.raml_description
+			.clone()
+			.unwrap_or_else(|| DescriptionComposition::default().compose(self,),);
+		for line in description.lines() {
+			lines.push(if line.is_empty() { String::new() } else { format!("  {line}") },);
+		}
 		if let RamlType::Enum(items, _,) = &self.type_on_raml {
 			lines.push(format!("enum:"),);
 			items.iter().for_each(|item| lines.push(format!("  - \"{item}\""),),);
 		}
+		if let (RamlType::String, Some(max_length,),) = (&self.type_on_raml, self.max_length,) {
+			lines.push(format!("maxLength: {max_length}"),);
+		}
+		match &self.default_value {
+			Some(DefaultValue::Literal(value,),) => lines.push(format!("default: {value}"),),
+			Some(DefaultValue::Formula(formula,),) => {
+				lines.push(format!("{DEFAULT_FORMULA_ANNOTATION}: {}", quote(formula,)),)
+			},
+			None => (),
+		}
 		lines.push(format!("example:"),);
 		lines.push(format!("  {}", self.example),);
 
-		let rslt = lines.join("\n    ",);
+		// blank lines of multi-line descriptions stay empty
+		let rslt = lines.join("\n    ",).replace("\n    \n", "\n\n",);
 		rslt
 	}
 
 	pub fn set_enum_variant(&mut self, variant_list: &Vec<Node,>,) -> Rslt<(),> {
 		if let RamlType::Enum(var, _,) = &mut self.type_on_raml {
-			let variant = get_enum_variant(variant_list, &self.name,);
+			let (variant, default,) = get_enum_variant(variant_list, &self.name,);
 			*var = variant;
-			self.example = format!("\"{}\"", var.get(0).unwrap_or(&"".to_string()));
+			// a literal `<defaultValue>` is only known to be a variant once the variants are set
+			self.default_value = match self.default_value.take() {
+				Some(DefaultValue::Formula(formula,),) => Some(DefaultValue::from_formula(
+					&formula,
+					&self.type_on_raml,
+					self.max_length,
+				),),
+				None => default.map(|value| DefaultValue::Literal(quote(&value,),),),
+				literal => literal,
+			};
+			self.example = default_example(&self.type_on_raml, self.max_length,);
 			Ok((),)
 		} else {
 			Err(anyhow!("expect RamlType::Enum, found {:?}", self.type_on_raml),)
@@ -162,7 +359,60 @@ impl RamlTypesMetadata {
 	}
 }
 
-#[derive(PartialEq, Eq, Debug,)]
+/// `<defaultValue>` of a field, or the default value of a picklist
+#[derive(PartialEq, Eq, Debug, Clone,)]
+pub enum DefaultValue {
+	/// raml literal as written in the `default` facet, e.g. `false`, `0`, `"未記入"`
+	Literal(String,),
+	/// `<defaultValue>` salesforce evaluates on insert, e.g. `TODAY()`, `$User.Id`
+	Formula(String,),
+}
+
+impl DefaultValue {
+	/// `<defaultValue>` is a formula. booleans, numbers and string literals matching the type
+	/// of the field are taken as literals. a string literal must fit `max_length` and be one
+	/// of the variants of an enum, or it stays a formula
+	pub fn from_formula(formula: &str, raml_type: &RamlType, max_length: Option<usize,>,) -> Self {
+		let formula = formula.trim();
+		let (items, base_type,) = match raml_type {
+			RamlType::Enum(items, base_type,) => (Some(items,), base_type.as_ref(),),
+			raml_type => (None, raml_type,),
+		};
+		let text = string_literal(formula,);
+		let is_literal = match (base_type, &text,) {
+			(RamlType::Boolean, _,) => matches!(formula, "true" | "false"),
+			(RamlType::Number, _,) => formula.parse::<f64>().is_ok(),
+			(RamlType::String, Some(text,),) => {
+				max_length.is_none_or(|max_length| text.chars().count() <= max_length,)
+					&& items.is_none_or(|items| items.contains(text,),)
+			},
+			_ => false,
+		};
+		match text {
+			Some(text,) if is_literal => Self::Literal(quote(&text,),),
+			_ if is_literal => Self::Literal(formula.to_string(),),
+			_ => Self::Formula(formula.to_string(),),
+		}
+	}
+}
+
+/// text of a formula that is a single string literal, `"abc"` or `'abc'`
+fn string_literal(formula: &str,) -> Option<String,> {
+	let delimiter = formula.chars().next().filter(|c| matches!(c, '"' | '\''),)?;
+	let inner = formula.strip_prefix(delimiter,)?.strip_suffix(delimiter,)?;
+	let mut text = String::new();
+	let mut chars = inner.chars();
+	while let Some(c,) = chars.next() {
+		match c {
+			'\\' => text.push(chars.next()?,),
+			c if c == delimiter => return None,
+			c => text.push(c,),
+		}
+	}
+	Some(text,)
+}
+
+#[derive(PartialEq, Eq, Debug, Clone,)]
 pub enum RamlType {
 	// String(Option<RegexPattern,>,),
 	// Number(Option<NumberFormat,>,),
@@ -171,6 +421,7 @@ pub enum RamlType {
 	Enum(Vec<String,>, Box<Self,>,),
 	Boolean,
 	Date,
+	DateTime,
 	Any,
 }
 
@@ -182,6 +433,7 @@ impl ToString for RamlType {
 			RamlType::Enum(_items, raml_type,) => raml_type.to_string(),
 			RamlType::Boolean => "boolean".to_string(),
 			RamlType::Date => "date".to_string(),
+			RamlType::DateTime => "datetime".to_string(),
 			RamlType::Any => "any".to_string(),
 		}
 	}
@@ -210,17 +462,136 @@ fn get_text_of_node<'a,>(n: &Node<'a, 'a,>,) -> String {
 	n.text().unwrap().to_string()
 }
 
+/// returns raml type and example for the salesforce field type `sf_type`.
+/// `max_length` is overwritten for types with fixed length
+pub(crate) fn sf_type_to_raml(
+	sf_type: &str,
+	max_length: &mut Option<usize,>,
+) -> (RamlType, String,) {
+	const SFID_LEN: usize = 18;
+	let raml_type = match sf_type {
+		"Lookup" | "MasterDetail" | "Id" => {
+			max_length.replace(SFID_LEN,);
+			return (RamlType::String, format!("\"{}\"", "X".repeat(SFID_LEN)),);
+		},
+		// variants are set later by `set_enum_variant`
+		"Picklist" => RamlType::Enum(vec![], Box::new(RamlType::String,),),
+		"Number" => RamlType::Number,
+		"Checkbox" => RamlType::Boolean,
+		"Date" => RamlType::Date,
+		"DateTime" => RamlType::DateTime,
+		_a => {
+			// println!("{_a}");
+			RamlType::String
+		},
+	};
+	let example = default_example(&raml_type, *max_length,);
+	(raml_type, example,)
+}
+
 pub fn create_raml_file(data: RamlMetadataStream, filename: impl AsRef<Path,>,) -> Rslt<(),> {
+	create_raml_file_with_mode(data, filename, GenerationMode::Flat,)
+}
+
+pub fn create_raml_file_with_mode(
+	data: RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	mode: GenerationMode,
+) -> Rslt<(),> {
+	let contents = render_raml(&data, mode,)?;
+	std::fs::write(filename, contents,)?;
+	Ok((),)
+}
+
+/// renders whole raml library without touching file system
+pub fn render_raml(data: &RamlMetadataStream, mode: GenerationMode,) -> Rslt<String,> {
+	data.validate_examples()?;
 	let mut contents =
 		data.0.iter().map(|metadata| metadata.format_as_raml(),).collect::<VecDeque<String,>>();
-	contents.push_front(RAML_HEAD.to_string(),);
+	let has_default_formula = data
+		.0
+		.iter()
+		.any(|metadata| matches!(metadata.default_value, Some(DefaultValue::Formula(_,),)),);
+	if has_default_formula {
+		contents.push_front(RAML_HEAD.replace("types:", DEFAULT_FORMULA_DECLARATION,),);
+	} else {
+		contents.push_front(RAML_HEAD.to_string(),);
+	}
+
+	match mode {
+		GenerationMode::Flat => (),
+		GenerationMode::RequestResponse => {
+			let base = type_base_name(data.object_name(),)?;
+			let writable =
+				data.0.iter().filter(|metadata| !metadata.read_only,).map(as_declared_property,);
+			let all = data.0.iter().map(as_declared_property,);
+			contents.push_back(format_object_as_raml(&format!("{base}Request"), None, writable,),);
+			contents.push_back(format_object_as_raml(&format!("{base}Response"), None, all,),);
+		},
+		GenerationMode::Crud => {
+			let base = type_base_name(data.object_name(),)?;
+			let update = format!("{base}Update");
+			let create = format!("{base}Create");
+
+			let writable = data.0.iter().filter(|metadata| !metadata.read_only,);
+			let all_optional = writable.clone().map(|metadata| (metadata, false,),);
+			// redeclaring an optional property as required narrows the parent type
+			let required =
+				writable.filter(|metadata| metadata.required,).map(|metadata| (metadata, true,),);
+			let read_only =
+				data.0.iter().filter(|metadata| metadata.read_only,).map(as_declared_property,);
+
+			contents.push_back(format_object_as_raml(&update, None, all_optional,),);
+			contents.push_back(format_object_as_raml(&create, Some(&update,), required,),);
+			contents.push_back(format_object_as_raml(base, Some(&create,), read_only,),);
+		},
+	}
+
 	let contents = contents.into_iter().collect::<Vec<String,>>().join("\n",);
+	Ok(contents,)
+}
 
-	std::fs::write(filename, contents,)?;
-	Ok((),)
+/// `Foo__c` -> `Foo`. object types are named after this
+pub fn type_base_name(object_name: &str,) -> Rslt<&str,> {
+	if object_name.is_empty() {
+		bail!("object name is unknown. set it with `RamlMetadataStream::with_object_name`")
+	}
+	Ok(object_name.strip_suffix("__c",).unwrap_or(object_name,),)
+}
+
+fn as_declared_property(metadata: &RamlTypesMetadata,) -> (&RamlTypesMetadata, bool,) {
+	(metadata, metadata.required,)
 }
 
-fn get_enum_variant<'a,>(variant_list: &Vec<Node,>, name: impl AsRef<str,>,) -> Vec<String,> {
+/// object type whose properties refer to the field types. each property comes with whether it is
+/// required
+fn format_object_as_raml<'a,>(
+	type_name: &str,
+	parent: Option<&str,>,
+	properties: impl Iterator<Item = (&'a RamlTypesMetadata, bool,),>,
+) -> String {
+	let mut lines = Vec::with_capacity(4,);
+	lines.push(format!("  {type_name}:"),);
+	lines.push(format!("type: {}", parent.unwrap_or("object")),);
+	let properties: Vec<_,> = properties
+		.map(|(metadata, required,)| {
+			let optional = if required { "" } else { "?" };
+			format!("  {}{optional}: {}", metadata.name, metadata.name)
+		},)
+		.collect();
+	if !properties.is_empty() {
+		lines.push("properties:".to_string(),);
+		lines.extend(properties,);
+	}
+
+	lines.join("\n    ",)
+}
+
+/// values of the picklist `name`, and the one marked `<default>true</default>`
+fn get_enum_variant<'a,>(
+	variant_list: &Vec<Node,>,
+	name: impl AsRef<str,>,
+) -> (Vec<String,>, Option<String,>,) {
 	// println!("{}", name.as_ref());
 	let target_node = variant_list.iter().find(|node| {
 		node.children()
@@ -231,27 +602,26 @@ fn get_enum_variant<'a,>(variant_list: &Vec<Node,>, name: impl AsRef<str,>,) ->
 	},);
 
 	if target_node.is_none() {
-		return vec![];
+		return (vec![], None,);
 	}
 
-	let variants = target_node
+	let variants: Vec<(String, bool,),> = target_node
 		.unwrap()
 		.children()
 		.filter(|child| child.tag_name().name() == "values",)
-		.map(|child| -> Rslt<String,> {
-			let value = child
-				.children()
-				.find(|child| child.tag_name().name() == "fullName",)
-				.unwrap()
-				.text()
-				.unwrap();
-			let value = urlencoding::decode(value,)?.into_owned();
-			Ok(value,)
+		.map(|child| -> Rslt<(String, bool,),> {
+			let text_of = |tag: &str| {
+				child.children().find(|child| child.tag_name().name() == tag,)?.text()
+			};
+			let value = urlencoding::decode(text_of("fullName",).unwrap(),)?.into_owned();
+			Ok((value, text_of("default",) == Some("true",),),)
 		},)
 		.try_collect()
 		.unwrap();
 
-	variants
+	let default = variants.iter().find(|(_, is_default,)| *is_default,);
+	let default = default.map(|(value, _,)| value.clone(),);
+	(variants.into_iter().map(|(value, _,)| value,).collect(), default,)
 }
 
 fn enum_variant_list<'a,>(custom_object: &Node<'a, 'a,>,) -> Vec<Node<'a, 'a,>,> {
@@ -270,8 +640,20 @@ fn enum_variant_list<'a,>(custom_object: &Node<'a, 'a,>,) -> Vec<Node<'a, 'a,>,>
 }
 
 pub fn create_raml_metadata_stream(path: impl AsRef<Path,>,) -> Rslt<RamlMetadataStream,> {
-	parse_from_path!(path, let doc);
-	RamlMetadataStream::new(&doc,)
+	parse_from_path!(path.as_ref(), let doc);
+	let stream = RamlMetadataStream::new(&doc,)?;
+	if !stream.object_name().is_empty() {
+		return Ok(stream,);
+	}
+
+	// `Foo__c.object` or `Foo__c.object-meta.xml`
+	let object_name = path
+		.as_ref()
+		.file_name()
+		.and_then(|name| name.to_str(),)
+		.and_then(|name| name.split('.',).next(),)
+		.unwrap_or_default();
+	Ok(stream.with_object_name(object_name,),)
 }
 
 mod tests {
@@ -285,6 +667,7 @@ mod tests {
 	const IC_PATH: &str = "data/IndividualContract__c.object";
 	const SOEC_PATH: &str = "data/SalesOrderEmploymentConditions__c.object";
 	const RAML_ARTICLE_PATH: &str = "data/xxx.raml";
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
 
 	fn raml_metadata_template() -> Rslt<Vec<RamlTypesMetadata,>,> {
 		parse_from_path!(IC_PATH, let doc);
@@ -378,12 +761,22 @@ mod tests {
 		let raml_type = RamlTypesMetadata::new(fields,)?;
 
 		let answer = RamlTypesMetadata {
-			name:         "AccessCode__c".to_string(),
-			type_on_raml: RamlType::Any,
-			desc:         "電子契約-アクセスコード".to_string(),
-			example:      "\"XXX\"".to_string(),
-			max_length:   Some(18,),
-			required:     false,
+			name:             "AccessCode__c".to_string(),
+			sf_type:          "Text".to_string(),
+			type_on_raml:     RamlType::Any,
+			desc:             "電子契約-アクセスコード".to_string(),
+			description:      None,
+			help_text:        None,
+			translations:     vec![],
+			raml_description: None,
+			example:          "\"XXX\"".to_string(),
+			default_value:    None,
+			max_length:       Some(18,),
+			precision:        None,
+			scale:            None,
+			reference_to:     vec![],
+			required:         false,
+			read_only:        false,
 		};
 
 		assert_eq!(answer, raml_type);
@@ -409,6 +802,7 @@ mod tests {
 		let formatted = rml_metadata[0].format_as_raml();
 		let answer = r#"  AccessCode__c:
     type: any
+    displayName: "電子契約-アクセスコード"
     description: |
       電子契約-アクセスコード
     example:
@@ -430,6 +824,7 @@ mod tests {
 			.format_as_raml();
 		let answer = r#"  Agreement__c:
     type: string
+    displayName: "36協定区分"
     description: |
       36協定区分
     enum:
@@ -509,4 +904,139 @@ mod tests {
 		assert_eq!(variants.len(), 11);
 		Ok((),)
 	}
+
+	#[test]
+	fn test_read_only_fields() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let read_only: Vec<_,> = raml_stream
+			.fields()
+			.iter()
+			.filter(|raml| raml.read_only,)
+			.map(|raml| raml.name.as_str(),)
+			.collect();
+		assert_eq!(read_only, ["TotalAmount__c", "ContractCount__c", "ContractNo__c"]);
+
+		let count = raml_stream.fields().iter().find(|raml| raml.name == "ContractCount__c",);
+		assert_eq!(count.unwrap().type_on_raml, RamlType::Number);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_reference_fields() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream("tests/fixtures/Contract__c.object",)?;
+		for (name, sf_type,) in [("Sample__c", "MasterDetail",), ("Signer__c", "Lookup",)] {
+			let raml = raml_stream.fields().iter().find(|raml| raml.name == name,).unwrap();
+			assert_eq!(raml.sf_type, sf_type);
+			assert_eq!(raml.type_on_raml, RamlType::String);
+			assert_eq!(raml.max_length, Some(18));
+			assert_eq!(raml.example, format!("\"{}\"", "X".repeat(18)));
+		}
+		Ok((),)
+	}
+
+	#[test]
+	fn test_object_name_from_path() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		assert_eq!(raml_stream.object_name(), "Sample__c");
+		assert_eq!(type_base_name(raml_stream.object_name())?, "Sample");
+		Ok((),)
+	}
+
+	#[test]
+	fn test_render_request_response() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let rendered = render_raml(&raml_stream, GenerationMode::RequestResponse,)?;
+
+		let (_, request,) = rendered.split_once("  SampleRequest:\n",).unwrap();
+		let (request, response,) = request.split_once("  SampleResponse:\n",).unwrap();
+		assert!(request.contains("      Agreement__c: Agreement__c\n"));
+		assert!(request.contains("      AccessCode__c?: AccessCode__c\n"));
+		assert!(!request.contains("TotalAmount__c"));
+		assert!(response.contains("      TotalAmount__c?: TotalAmount__c"));
+		assert!(response.contains("      ContractNo__c?: ContractNo__c"));
+		Ok((),)
+	}
+
+	#[test]
+	fn test_render_crud() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let rendered = render_raml(&raml_stream, GenerationMode::Crud,)?;
+
+		let (_, update,) = rendered.split_once("  SampleUpdate:\n    type: object\n",).unwrap();
+		let (update, create,) =
+			update.split_once("  SampleCreate:\n    type: SampleUpdate\n",).unwrap();
+		let (create, read,) = create.split_once("  Sample:\n    type: SampleCreate\n",).unwrap();
+
+		assert!(update.contains("      Agreement__c?: Agreement__c\n"));
+		assert!(update.contains("      AccessCode__c?: AccessCode__c\n"));
+		assert!(!update.contains("TotalAmount__c"));
+
+		assert!(create.contains("      Agreement__c: Agreement__c\n"));
+		assert!(create.contains("      Quantity__c: Quantity__c\n"));
+		assert!(!create.contains("AccessCode__c"));
+
+		assert!(read.contains("      TotalAmount__c?: TotalAmount__c"));
+		assert!(!read.contains("Agreement__c"));
+		Ok((),)
+	}
+
+	#[test]
+	fn test_with_system_fields() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?
+			.with_system_fields(&["Id", "CreatedDate", "OwnerId",],)?;
+		let names: Vec<_,> =
+			raml_stream.fields()[..3].iter().map(|raml| raml.name.as_str(),).collect();
+		assert_eq!(names, ["Id", "CreatedDate", "OwnerId"]);
+		assert_eq!(raml_stream.fields()[1].type_on_raml, RamlType::DateTime);
+
+		let rendered = render_raml(&raml_stream, GenerationMode::Crud,)?;
+		let (_, read,) = rendered.split_once("  Sample:\n",).unwrap();
+		assert!(read.contains("      Id: Id\n"));
+		assert!(read.contains("      CreatedDate: CreatedDate\n"));
+		assert!(!read.contains("OwnerId"));
+
+		assert!(create_raml_metadata_stream(SAMPLE_PATH,)?.with_system_fields(&["Foo"],).is_err());
+		Ok((),)
+	}
+
+	#[test]
+	fn test_default_values() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let rendered = render_raml(&raml_stream, GenerationMode::Flat,)?;
+		assert!(rendered.starts_with(RAML_HEAD));
+		assert!(!rendered.contains("    default: \"89：一般\"\n"));
+		assert!(rendered.contains("    displayName: \"締結済\"\n    description: |\n      締結済\n    \
+		                           default: false\n"));
+
+		let raml_stream = create_raml_metadata_stream("tests/fixtures/Contract__c.object",)?;
+		let defaults: Vec<_,> =
+			raml_stream.fields().iter().map(|metadata| metadata.default_value.clone(),).collect();
+		assert_eq!(defaults[3..], [
+			Some(DefaultValue::Literal("\"未記入\"".to_string())),
+			Some(DefaultValue::Formula("TODAY()".to_string())),
+			Some(DefaultValue::Literal("\"未署名\"".to_string())),
+		]);
+		let rendered = render_raml(&raml_stream, GenerationMode::Flat,)?;
+		assert!(rendered.contains("      - \"署名済\"\n    default: \"未署名\"\n"));
+		assert!(rendered.contains("annotationTypes:\n  defaultFormula: string\n\ntypes:"));
+		assert!(rendered.contains("    (defaultFormula): \"TODAY()\"\n"));
+
+		let from_formula = DefaultValue::from_formula;
+		let literal = |value: &str| DefaultValue::Literal(value.to_string(),);
+		let status = RamlType::Enum(vec!["a".to_string()], Box::new(RamlType::String,),);
+		assert_eq!(from_formula(" 0.5 ", &RamlType::Number, None,), literal("0.5"));
+		assert_eq!(from_formula("'it\\'s'", &RamlType::String, Some(4,),), literal("\"it's\""));
+		assert_eq!(from_formula("\"a\"", &status, None,), literal("\"a\""));
+		for (formula, raml_type, max_length,) in [
+			("\"A\" & \"B\"", RamlType::String, None,),
+			("$User.Id", RamlType::String, None,),
+			("1", RamlType::Boolean, None,),
+			("'it\\'s'", RamlType::String, Some(3,),),
+			("\"b\"", status.clone(), None,),
+		] {
+			let default_value = from_formula(formula, &raml_type, max_length,);
+			assert_eq!(default_value, DefaultValue::Formula(formula.into()));
+		}
+		Ok((),)
+	}
 }
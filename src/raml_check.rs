@@ -0,0 +1,85 @@
+use crate::raml::GenerationMode;
+use crate::raml::RamlMetadataStream;
+use crate::raml::render_raml;
+use crate::raml_merge::MergeConflict;
+use crate::raml_merge::render_merged_raml;
+use crate::read_file;
+use anyhow::Result as Rslt;
+use similar::TextDiff;
+use std::path::Path;
+
+impl RamlMetadataStream {
+	/// unified diff between `data/{filename}` and the library `create_raml_file_with_mode` would
+	/// write. `None` if the file is up to date
+	pub fn check_raml_file(self, filename: String, mode: GenerationMode,) -> Rslt<Option<String,>,> {
+		check_raml_file(&self, format!("data/{filename}"), mode,)
+	}
+
+	/// same as [`RamlMetadataStream::check_raml_file`] against the library `merge_raml_file`
+	/// would write
+	pub fn check_merged_raml_file(
+		self,
+		filename: String,
+		mode: GenerationMode,
+		user_facets: &[String],
+	) -> Rslt<(Option<String,>, Vec<MergeConflict,>,),> {
+		let filename = format!("data/{filename}");
+		let (expected, conflicts,) = render_merged_raml(&self, &filename, mode, user_facets,)?;
+		Ok((stale_diff(filename, &expected,)?, conflicts,),)
+	}
+}
+
+pub fn check_raml_file(
+	data: &RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	mode: GenerationMode,
+) -> Rslt<Option<String,>,> {
+	let expected = render_raml(data, mode,)?;
+	stale_diff(filename, &expected,)
+}
+
+/// unified diff from `filename` to `expected`. a missing file reads as empty. `None` if they
+/// match
+pub fn stale_diff(filename: impl AsRef<Path,>, expected: &str,) -> Rslt<Option<String,>,> {
+	let path = filename.as_ref();
+	let actual = if path.exists() { read_file(path,)? } else { String::new() };
+	if actual == expected {
+		return Ok(None,);
+	}
+
+	let name = path.display().to_string();
+	let diff = TextDiff::from_lines(actual.as_str(), expected,)
+		.unified_diff()
+		.header(&name, &format!("{name} (generated)"),)
+		.to_string();
+	Ok(Some(diff,),)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_file;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_check_raml_file() -> Rslt<(),> {
+		let path = std::env::temp_dir().join(format!("xraml_check_{}.raml", std::process::id()),);
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		assert!(check_raml_file(&raml_stream, &path, GenerationMode::Flat,)?.is_some());
+
+		create_raml_file(raml_stream.clone(), &path,)?;
+		assert_eq!(check_raml_file(&raml_stream, &path, GenerationMode::Flat,)?, None);
+
+		let stale = read_file(&path,)?.replace("電子契約-アクセスコード", "アクセスコード",);
+		std::fs::write(&path, stale,)?;
+		let diff = check_raml_file(&raml_stream, &path, GenerationMode::Flat,)?.unwrap();
+		std::fs::remove_file(&path,)?;
+
+		assert!(diff.starts_with(&format!("--- {}\n+++ {0} (generated)\n@@ ", path.display())));
+		let changed = "\n-    displayName: \"アクセスコード\"\n+    displayName: \"電子契約-アクセスコード\"\n";
+		assert!(diff.contains(changed));
+		Ok((),)
+	}
+}
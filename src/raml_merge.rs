@@ -0,0 +1,321 @@
+use crate::raml::DEFAULT_FORMULA_ANNOTATION;
+use crate::raml::GenerationMode;
+use crate::raml::RamlMetadataStream;
+use crate::raml::render_raml;
+use crate::raml_parser::RamlLibrary;
+use crate::raml_parser::Yaml;
+use crate::raml_parser::parse_raml_library;
+use crate::raml_parser::unquote;
+use crate::read_file;
+use anyhow::Result as Rslt;
+use std::fmt::Display;
+use std::path::Path;
+
+/// annotation listing facets of a type that regeneration must not touch
+///
+/// ```yaml
+///   AccessCode__c:
+///     (userOwned): [description, example]
+/// ```
+pub const USER_OWNED_ANNOTATION: &str = "(userOwned)";
+
+/// facets xraml writes. any other facet on a generated type was added by hand and is kept
+pub const GENERATED_FACETS: &[&str] = &[
+	"type",
+	"displayName",
+	"description",
+	"enum",
+	"maxLength",
+	"default",
+	DEFAULT_FORMULA_ANNOTATION,
+	"example",
+	"examples",
+	"properties",
+];
+
+/// facets that change the api contract. a user-owned one disagreeing with the metadata is a
+/// conflict
+const CONTRACT_FACETS: &[&str] = &["type", "enum", "properties", "maxLength",];
+
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct MergeConflict {
+	pub type_name: String,
+	pub facet:     Option<String,>,
+	pub message:   String,
+}
+
+impl Display for MergeConflict {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_,>,) -> std::fmt::Result {
+		match &self.facet {
+			Some(facet,) => write!(f, "{}.{facet}: {}", self.type_name, self.message),
+			None => write!(f, "{}: {}", self.type_name, self.message),
+		}
+	}
+}
+
+impl RamlMetadataStream {
+	pub fn merge_raml_file(
+		self,
+		filename: String,
+		mode: GenerationMode,
+		user_facets: &[String],
+	) -> Rslt<Vec<MergeConflict,>,> {
+		merge_raml_file(self, format!("data/{filename}"), mode, user_facets,)
+	}
+}
+
+/// regenerates `filename` from `data`, keeping hand edits of the library already there. writes
+/// a fresh library if there is none
+pub fn merge_raml_file(
+	data: RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	mode: GenerationMode,
+	user_facets: &[String],
+) -> Rslt<Vec<MergeConflict,>,> {
+	let (merged, conflicts,) = render_merged_raml(&data, &filename, mode, user_facets,)?;
+	std::fs::write(filename, merged,)?;
+	Ok(conflicts,)
+}
+
+/// library [`merge_raml_file`] would write, without writing it
+pub fn render_merged_raml(
+	data: &RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	mode: GenerationMode,
+	user_facets: &[String],
+) -> Rslt<(String, Vec<MergeConflict,>,),> {
+	let generated = render_raml(data, mode,)?;
+	if !filename.as_ref().exists() {
+		return Ok((generated, vec![],),);
+	}
+
+	let existing = parse_raml_library(&read_file(&filename,)?,)?;
+	let generated = parse_raml_library(&generated,)?;
+	let (merged, conflicts,) = merge_raml(&existing, &generated, user_facets,);
+	Ok((merged.to_raml(), conflicts,),)
+}
+
+/// takes types from `generated`, then puts back facets of `existing` that are
+/// - listed in `user_facets` or in the `(userOwned)` annotation of the type
+/// - annotations xraml does not write
+/// - never written by xraml, such as `pattern`
+///
+/// types only in `existing` are dropped unless they are annotated `(userOwned)`
+pub fn merge_raml(
+	existing: &RamlLibrary,
+	generated: &RamlLibrary,
+	user_facets: &[String],
+) -> (RamlLibrary, Vec<MergeConflict,>,) {
+	let mut conflicts = vec![];
+	let mut types: Vec<(String, Yaml,),> = generated
+		.types
+		.iter()
+		.map(|(name, declaration,)| match existing.get(name,) {
+			Some(old,) => {
+				(name.clone(), merge_type(name, old, declaration, user_facets, &mut conflicts,),)
+			},
+			None => (name.clone(), declaration.clone(),),
+		},)
+		.collect();
+
+	for (name, old,) in &existing.types {
+		if generated.get(name,).is_some() {
+			continue;
+		}
+
+		let conflict = |message: String| MergeConflict {
+			type_name: name.clone(),
+			facet: None,
+			message,
+		};
+		let hand_edits = hand_edited_facets(old,);
+		if old.get(USER_OWNED_ANNOTATION,).is_some() {
+			let message = "no longer generated from metadata. kept as user-owned";
+			conflicts.push(conflict(message.to_string(),),);
+			types.push((name.clone(), old.clone(),),);
+		} else if !hand_edits.is_empty() {
+			conflicts.push(conflict(format!(
+				"no longer generated from metadata. hand edits dropped: {}",
+				hand_edits.join(", ",)
+			),),);
+		}
+	}
+
+	let mut other = existing.other.clone();
+	if let Some((_, Yaml::Map(declarations,),),) =
+		generated.other.iter().find(|(key, _,)| key == "annotationTypes",)
+	{
+		for (name, declaration,) in declarations {
+			declare_annotation(&mut other, name, declaration,);
+		}
+	}
+	let uses_annotation =
+		types.iter().any(|(_, declaration,)| declaration.get(USER_OWNED_ANNOTATION,).is_some(),);
+	if uses_annotation {
+		let name = USER_OWNED_ANNOTATION.trim_matches(['(', ')',],);
+		declare_annotation(&mut other, name, &Yaml::Scalar("string[]".to_string(),),);
+	}
+
+	(RamlLibrary { other, types, }, conflicts,)
+}
+
+fn merge_type(
+	name: &str,
+	existing: &Yaml,
+	generated: &Yaml,
+	user_facets: &[String],
+	conflicts: &mut Vec<MergeConflict,>,
+) -> Yaml {
+	let (Yaml::Map(old,), Yaml::Map(new,),) = (existing, generated,) else {
+		return generated.clone();
+	};
+
+	let mut owned: Vec<String,> = user_facets.to_vec();
+	match existing.get(USER_OWNED_ANNOTATION,) {
+		Some(Yaml::List(facets,),) => owned.extend(facets.iter().filter_map(Yaml::as_text,),),
+		Some(facet,) => owned.extend(facet.as_text(),),
+		None => (),
+	}
+
+	let mut merged = new.clone();
+	for (facet, value,) in old {
+		let is_annotation = facet.starts_with('(',) && !GENERATED_FACETS.contains(&facet.as_str(),);
+		let is_owned = is_annotation || owned.contains(facet,);
+		let position = merged.iter().position(|(key, _,)| key == facet,);
+		match position {
+			Some(i,) if is_owned => {
+				if CONTRACT_FACETS.contains(&facet.as_str(),) && merged[i].1 != *value {
+					conflicts.push(MergeConflict {
+						type_name: name.to_string(),
+						facet:     Some(facet.clone(),),
+						message:   "user-owned value differs from metadata. kept user's".into(),
+					},);
+				}
+				merged[i].1 = value.clone();
+			},
+			None if is_owned || !GENERATED_FACETS.contains(&facet.as_str(),) => {
+				merged.push((facet.clone(), value.clone(),),)
+			},
+			_ => (),
+		}
+	}
+
+	Yaml::Map(merged,)
+}
+
+fn hand_edited_facets(declaration: &Yaml,) -> Vec<String,> {
+	match declaration {
+		Yaml::Map(facets,) => facets
+			.iter()
+			.map(|(facet, _,)| unquote(facet,),)
+			.filter(|facet| !GENERATED_FACETS.contains(&facet.as_str(),),)
+			.collect(),
+		_ => vec![],
+	}
+}
+
+/// adds `name` to `annotationTypes` unless the library declares it already
+fn declare_annotation(other: &mut Vec<(String, Yaml,),>, name: &str, declaration: &Yaml,) {
+	let declaration = (name.to_string(), declaration.clone(),);
+	match other.iter_mut().find(|(key, _,)| key == "annotationTypes",) {
+		Some((_, Yaml::Map(annotation_types,),),) => {
+			if annotation_types.iter().all(|(key, _,)| key != name,) {
+				annotation_types.push(declaration,);
+			}
+		},
+		Some((_, value,),) => *value = Yaml::Map(vec![declaration],),
+		None => other.push(("annotationTypes".to_string(), Yaml::Map(vec![declaration],),),),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	fn generated() -> Rslt<(String, RamlLibrary,),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let rendered = render_raml(&raml_stream, GenerationMode::Crud,)?;
+		let library = parse_raml_library(&rendered,)?;
+		Ok((rendered, library,),)
+	}
+
+	#[test]
+	fn test_merge_without_edits() -> Rslt<(),> {
+		let (rendered, library,) = generated()?;
+		let (merged, conflicts,) = merge_raml(&library, &library, &[],);
+		assert_eq!(merged.to_raml(), rendered);
+		assert!(conflicts.is_empty());
+		Ok((),)
+	}
+
+	#[test]
+	fn test_merge_keeps_hand_edits() -> Rslt<(),> {
+		let (rendered, library,) = generated()?;
+		let edited = rendered
+			.replace(
+				"  AccessCode__c:\n    type: string\n",
+				"  AccessCode__c:\n    type: string\n    pattern: ^[0-9]+$\n",
+			)
+			.replace(
+				"      電子契約サービスから払い出される | 区切りなしの照会コード\n\n      契約書の URL に\
+				 含まれる 18 桁の英数字",
+				"      契約書を開くためのコード",
+			)
+			.replace("  Agreement__c:\n", "  Agreement__c:\n    (userOwned): [enum]\n",)
+			.replace("      - \"90：一般（フレックス）\"\n", "",)
+			.replace("      \"XXX\"", "      \"hand written\"",);
+		let obsolete = "\n  Obsolete__c:\n    type: string\n    pattern: x";
+		let existing = parse_raml_library(&(edited + obsolete),)?;
+
+		let user_facets = vec!["description".to_string()];
+		let (merged, conflicts,) = merge_raml(&existing, &library, &user_facets,);
+
+		let access_code = merged.get("AccessCode__c",).unwrap();
+		assert_eq!(access_code.get("pattern").unwrap().as_text().unwrap(), "^[0-9]+$");
+		assert_eq!(access_code.get("description").unwrap().as_text().unwrap(), "契約書を開くためのコード");
+		assert_eq!(access_code.get("example").unwrap().as_raw().unwrap(), "\"XXX\"");
+
+		let agreement = merged.get("Agreement__c",).unwrap();
+		let Some(Yaml::List(variants,),) = agreement.get("enum",) else { panic!("{agreement:?}") };
+		assert_eq!(variants.len(), 2);
+		assert!(merged.get("Obsolete__c").is_none());
+		assert!(merged.to_raml().contains("annotationTypes:\n  userOwned: string[]\n\ntypes:"));
+
+		let conflicts: Vec<_,> = conflicts.iter().map(|conflict| conflict.to_string(),).collect();
+		assert_eq!(conflicts, [
+			"Agreement__c.enum: user-owned value differs from metadata. kept user's",
+			"Obsolete__c: no longer generated from metadata. hand edits dropped: pattern",
+		]);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_merged_library_parses_again() -> Rslt<(),> {
+		let (rendered, library,) = generated()?;
+		let edited =
+			rendered.replace("  Agreement__c:\n", "  Agreement__c:\n    (userOwned): [example]\n",);
+		let (merged, _,) = merge_raml(&parse_raml_library(&edited,)?, &library, &[],);
+		let reparsed = parse_raml_library(&merged.to_raml(),)?;
+		assert_eq!(reparsed, merged);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_merge_regenerates_defaults() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream("tests/fixtures/Contract__c.object",)?;
+		let rendered = render_raml(&raml_stream, GenerationMode::Flat,)?;
+		let generated = parse_raml_library(&rendered,)?;
+		// a library written before the field got its default
+		let edited = rendered
+			.replace("annotationTypes:\n  defaultFormula: string\n\n", "",)
+			.replace("    (defaultFormula): \"TODAY()\"\n", "    (defaultFormula): \"NOW()\"\n",)
+			.replace("    default: \"未記入\"\n", "",);
+		let (merged, conflicts,) = merge_raml(&parse_raml_library(&edited,)?, &generated, &[],);
+		assert_eq!(merged.to_raml(), rendered);
+		assert!(conflicts.is_empty());
+		Ok((),)
+	}
+}
@@ -0,0 +1,623 @@
+use crate::raml::DEFAULT_FORMULA_ANNOTATION;
+use crate::raml::DefaultValue;
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use crate::read_file;
+use anyhow::Result as Rslt;
+use anyhow::bail;
+use std::path::Path;
+
+/// yaml subset used by raml libraries xraml emits: block maps, block lists, flow lists of
+/// scalars and `|` block scalars. plain and quoted scalars are kept as written
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub enum Yaml {
+	Scalar(String,),
+	/// `|` block scalar, indentation stripped
+	Block(String,),
+	List(Vec<Yaml,>,),
+	Map(Vec<(String, Yaml,),>,),
+}
+
+impl Yaml {
+	pub fn get(&self, key: &str,) -> Option<&Yaml,> {
+		match self {
+			Yaml::Map(entries,) => entries.iter().find(|(k, _,)| k == key,).map(|(_, v,)| v,),
+			_ => None,
+		}
+	}
+
+	/// text of a scalar with quotes removed
+	pub fn as_text(&self,) -> Option<String,> {
+		match self {
+			Yaml::Scalar(scalar,) => Some(unquote(scalar,),),
+			Yaml::Block(text,) => Some(text.clone(),),
+			_ => None,
+		}
+	}
+
+	/// scalar as written, quotes included
+	pub fn as_raw(&self,) -> Option<&str,> {
+		match self {
+			Yaml::Scalar(scalar,) => Some(scalar,),
+			Yaml::Block(text,) => Some(text,),
+			_ => None,
+		}
+	}
+}
+
+/// `types:` of a raml library in declaration order
+#[derive(Debug, Clone, PartialEq, Eq, Default,)]
+pub struct RamlLibrary {
+	/// top level nodes other than `types`, such as `uses` or `annotationTypes`
+	pub other: Vec<(String, Yaml,),>,
+	pub types: Vec<(String, Yaml,),>,
+}
+
+/// object type declaration such as `FooCreate` of `GenerationMode::Crud`
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct RamlObjectType {
+	pub name:       String,
+	/// `None` for plain `type: object`
+	pub parent:     Option<String,>,
+	/// property name, its type and whether it is required
+	pub properties: Vec<(String, String, bool,),>,
+}
+
+impl RamlLibrary {
+	pub fn get(&self, name: &str,) -> Option<&Yaml,> {
+		self.types.iter().find(|(n, _,)| n == name,).map(|(_, declaration,)| declaration,)
+	}
+
+	pub fn object_types(&self,) -> Vec<RamlObjectType,> {
+		self.types
+			.iter()
+			.filter(|(name, _,)| self.is_object_type(name,),)
+			.map(|(name, declaration,)| {
+				let parent = type_of(declaration,).filter(|parent| parent != "object",);
+				let properties = match declaration.get("properties",) {
+					Some(Yaml::Map(properties,),) => properties
+						.iter()
+						.map(|(property, value,)| {
+							let (property, optional,) = match property.strip_suffix('?',) {
+								Some(property,) => (property, true,),
+								None => (property.as_str(), false,),
+							};
+							let required = match value.get("required",) {
+								Some(required,) => required.as_raw() == Some("true",),
+								None => !optional,
+							};
+							let type_name = type_of(value,).unwrap_or(property.to_string(),);
+							(property.to_string(), type_name, required,)
+						},)
+						.collect(),
+					_ => vec![],
+				};
+				RamlObjectType { name: name.clone(), parent, properties, }
+			},)
+			.collect()
+	}
+
+	/// every type except object types, in declaration order
+	pub fn field_types(&self,) -> Rslt<Vec<RamlTypesMetadata,>,> {
+		self.types
+			.iter()
+			.filter(|(name, _,)| !self.is_object_type(name,),)
+			.map(|(name, declaration,)| to_metadata(name, declaration,),)
+			.try_collect()
+	}
+
+	/// field types with `required` taken from object types. when object types exist, fields
+	/// none of the writable ones (`*Request`, `*Update`) declare are read-only
+	pub fn to_metadata_stream(&self,) -> Rslt<RamlMetadataStream,> {
+		let objects = self.object_types();
+		let declares = |object: &RamlObjectType, name: &str, required: bool| {
+			object.properties.iter().any(|(property, _, r,)| property == name && (!required || *r),)
+		};
+		let is_writable_type = |object: &&RamlObjectType| {
+			object.name.ends_with("Request",) || object.name.ends_with("Update",)
+		};
+
+		let mut fields = self.field_types()?;
+		fields.iter_mut().for_each(|metadata| {
+			metadata.required |=
+				objects.iter().any(|object| declares(object, &metadata.name, true,),);
+			metadata.read_only = !objects.is_empty()
+				&& !objects
+					.iter()
+					.filter(is_writable_type,)
+					.any(|object| declares(object, &metadata.name, false,),);
+		},);
+
+		Ok(RamlMetadataStream::from_fields(fields, "",),)
+	}
+
+	/// renders the library in the layout of `raml::render_raml`, so an unchanged library gives
+	/// back the same text
+	pub fn to_raml(&self,) -> String {
+		let mut head = vec!["#%RAML 1.0 Library".to_string(), "".to_string()];
+		for (key, value,) in &self.other {
+			let mut lines = vec![];
+			yaml_entry_lines(key, value, 0, &mut lines,);
+			head.push(lines.join("\n",) + "\n",);
+		}
+		head.push("types:".to_string(),);
+
+		let mut lines = vec![head.join("\n",)];
+		for (name, declaration,) in &self.types {
+			yaml_entry_lines(name, declaration, 2, &mut lines,);
+		}
+		lines.join("\n",)
+	}
+
+	fn is_object_type(&self, name: &str,) -> bool {
+		let Some(declaration,) = self.get(name,) else { return false };
+		if declaration.get("properties",).is_some() {
+			return true;
+		}
+		match type_of(declaration,) {
+			Some(parent,) if parent == "object" => true,
+			// guards against `A: B` and `B: A`
+			Some(parent,) if parent != name => self.is_object_type(&parent,),
+			_ => false,
+		}
+	}
+}
+
+pub fn read_raml_library(path: impl AsRef<Path,>,) -> Rslt<RamlLibrary,> {
+	let content = read_file(path,)?;
+	parse_raml_library(&content,)
+}
+
+pub fn parse_raml_library(content: &str,) -> Rslt<RamlLibrary,> {
+	let mut lines = content.lines();
+	match lines.next() {
+		Some(head,) if head.trim_end() == "#%RAML 1.0 Library" => (),
+		head => bail!("expect `#%RAML 1.0 Library` at first line, found {head:?}"),
+	}
+
+	let lines: Vec<Line,> = lines.enumerate().map(|(i, text,)| Line::new(i + 2, text,),).collect();
+	let mut pos = 0;
+	skip_blank(&lines, &mut pos,);
+	if pos == lines.len() {
+		return Ok(RamlLibrary::default(),);
+	}
+
+	let indent = lines[pos].indent;
+	let Yaml::Map(root,) = parse_node(&lines, &mut pos, indent,)? else {
+		bail!("line {}: expect map at top level", lines[0].number)
+	};
+	let (types, other,): (Vec<_,>, Vec<_,>,) = root.into_iter().partition(|(key, _,)| key == "types",);
+	let types = match types.into_iter().next() {
+		Some((_, Yaml::Map(types,),),) => types,
+		Some((_, Yaml::Scalar(s,),),) if s.is_empty() => vec![],
+		Some(_,) => bail!("`types` has to be a map"),
+		None => vec![],
+	};
+
+	// `Foo: string` is a shorthand of `Foo: { type: string }`
+	let types = types
+		.into_iter()
+		.map(|(name, declaration,)| match declaration {
+			Yaml::Scalar(type_name,) => {
+				let facets = vec![("type".to_string(), Yaml::Scalar(type_name,),)];
+				(name, Yaml::Map(facets,),)
+			},
+			declaration => (name, declaration,),
+		},)
+		.collect();
+	Ok(RamlLibrary { other, types, },)
+}
+
+fn type_of(declaration: &Yaml,) -> Option<String,> {
+	match declaration {
+		Yaml::Scalar(type_name,) => Some(unquote(type_name,),),
+		declaration => declaration.get("type",)?.as_text(),
+	}
+}
+
+fn to_metadata(name: &str, declaration: &Yaml,) -> Rslt<RamlTypesMetadata,> {
+	let type_name = type_of(declaration,).unwrap_or("string".to_string(),);
+	let base = match type_name.as_str() {
+		"string" => RamlType::String,
+		"number" | "integer" => RamlType::Number,
+		"boolean" => RamlType::Boolean,
+		"date" | "date-only" => RamlType::Date,
+		"datetime" | "datetime-only" => RamlType::DateTime,
+		"any" => RamlType::Any,
+		t => bail!("type `{name}`: unsupported type `{t}`"),
+	};
+
+	let type_on_raml = match declaration.get("enum",) {
+		Some(Yaml::List(items,),) => {
+			let items = items.iter().filter_map(Yaml::as_text,).collect();
+			RamlType::Enum(items, Box::new(base,),)
+		},
+		Some(_,) => bail!("type `{name}`: `enum` has to be a list"),
+		None => base,
+	};
+
+	let sf_type = match &type_on_raml {
+		RamlType::String | RamlType::Any => "Text",
+		RamlType::Number => "Number",
+		RamlType::Enum(..,) => "Picklist",
+		RamlType::Boolean => "Checkbox",
+		RamlType::Date => "Date",
+		RamlType::DateTime => "DateTime",
+	};
+
+	let text = |facet: &str| declaration.get(facet,).and_then(Yaml::as_text,);
+	let max_length = match text("maxLength",) {
+		Some(max_length,) => Some(max_length.parse()?,),
+		None => None,
+	};
+
+	let default_value = match declaration.get(DEFAULT_FORMULA_ANNOTATION,) {
+		Some(formula,) => formula.as_text().map(DefaultValue::Formula,),
+		None => {
+			let raw = declaration.get("default",).and_then(Yaml::as_raw,);
+			raw.map(|raw| DefaultValue::Literal(raw.to_string(),),)
+		},
+	};
+
+	Ok(RamlTypesMetadata {
+		name: name.to_string(),
+		sf_type: sf_type.to_string(),
+		type_on_raml,
+		// libraries written before `displayName` carry the label in `description`
+		desc: text("displayName",).or_else(|| text("description",),).unwrap_or_default(),
+		description: None,
+		help_text: None,
+		translations: vec![],
+		raml_description: text("description",),
+		example: declaration
+			.get("example",)
+			.and_then(Yaml::as_raw,)
+			.unwrap_or_default()
+			.to_string(),
+		default_value,
+		max_length,
+		precision: None,
+		scale: None,
+		reference_to: vec![],
+		required: false,
+		read_only: false,
+	},)
+}
+
+fn yaml_entry_lines(key: &str, value: &Yaml, indent: usize, lines: &mut Vec<String,>,) {
+	let pad = " ".repeat(indent,);
+	match value {
+		// generated libraries put examples on their own line
+		Yaml::Scalar(scalar,) if key == "example" => {
+			lines.push(format!("{pad}{key}:"),);
+			lines.push(format!("{pad}  {scalar}"),);
+		},
+		Yaml::Scalar(scalar,) if scalar.is_empty() => lines.push(format!("{pad}{key}:"),),
+		Yaml::Scalar(scalar,) => lines.push(format!("{pad}{key}: {scalar}"),),
+		Yaml::Block(text,) => {
+			lines.push(format!("{pad}{key}: |"),);
+			text.lines().for_each(|line| {
+				lines.push(if line.is_empty() { String::new() } else { format!("{pad}  {line}") },)
+			},);
+		},
+		Yaml::List(items,) if items.is_empty() => lines.push(format!("{pad}{key}: []"),),
+		Yaml::Map(entries,) if entries.is_empty() => lines.push(format!("{pad}{key}: {{}}"),),
+		Yaml::List(_,) | Yaml::Map(_,) => {
+			lines.push(format!("{pad}{key}:"),);
+			yaml_value_lines(value, indent + 2, lines,);
+		},
+	}
+}
+
+fn yaml_value_lines(value: &Yaml, indent: usize, lines: &mut Vec<String,>,) {
+	let pad = " ".repeat(indent,);
+	match value {
+		Yaml::Map(entries,) => {
+			entries.iter().for_each(|(key, value,)| yaml_entry_lines(key, value, indent, lines,),)
+		},
+		Yaml::List(items,) => items.iter().for_each(|item| {
+			let first = lines.len();
+			yaml_value_lines(item, indent + 2, lines,);
+			if first < lines.len() {
+				lines[first].replace_range(..indent + 2, &format!("{pad}- "),);
+			}
+		},),
+		Yaml::Scalar(scalar,) | Yaml::Block(scalar,) => lines.push(format!("{pad}{scalar}"),),
+	}
+}
+
+struct Line<'a,> {
+	number: usize,
+	indent: usize,
+	/// without indentation
+	text:   &'a str,
+}
+
+impl<'a,> Line<'a,> {
+	fn new(number: usize, raw: &'a str,) -> Self {
+		let text = raw.trim_start_matches(' ',).trim_end();
+		Self { number, indent: raw.len() - raw.trim_start_matches(' ',).len(), text, }
+	}
+
+	fn is_blank(&self,) -> bool {
+		self.text.is_empty() || self.text.starts_with('#',)
+	}
+
+	fn is_list_item(&self,) -> bool {
+		self.text == "-" || self.text.starts_with("- ",)
+	}
+}
+
+fn skip_blank(lines: &[Line], pos: &mut usize,) {
+	while *pos < lines.len() && lines[*pos].is_blank() {
+		*pos += 1;
+	}
+}
+
+/// parses the node starting at `lines[*pos]`, which is indented by `indent`
+fn parse_node(lines: &[Line], pos: &mut usize, indent: usize,) -> Rslt<Yaml,> {
+	let first = &lines[*pos];
+	if first.is_list_item() {
+		return parse_list(lines, pos, indent,);
+	}
+	if split_key(first.text,).is_none() {
+		// plain scalar, possibly continued on following lines
+		let mut words = vec![first.text];
+		*pos += 1;
+		while *pos < lines.len() && !lines[*pos].is_blank() && lines[*pos].indent >= indent {
+			words.push(lines[*pos].text,);
+			*pos += 1;
+		}
+		return Ok(Yaml::Scalar(words.join(" ",),),);
+	}
+
+	let mut entries = vec![];
+	loop {
+		skip_blank(lines, pos,);
+		if *pos == lines.len() || lines[*pos].indent < indent {
+			break;
+		}
+
+		let line = &lines[*pos];
+		if line.indent > indent {
+			bail!("line {}: unexpected indentation", line.number)
+		}
+		let Some((key, value,),) = split_key(line.text,) else {
+			bail!("line {}: expect `key: value`, found `{}`", line.number, line.text)
+		};
+		*pos += 1;
+
+		let value = match value {
+			"|" | "|-" | "|+" => parse_block_scalar(lines, pos, indent,),
+			v if v.starts_with('[',) && v.ends_with(']',) => {
+				let items = split_flow_items(&v[1..v.len() - 1],);
+				Yaml::List(items.into_iter().map(|s| Yaml::Scalar(s.to_string(),),).collect(),)
+			},
+			"" => {
+				skip_blank(lines, pos,);
+				match lines.get(*pos,) {
+					Some(next,) if next.indent > indent => parse_node(lines, pos, next.indent,)?,
+					// `key:` followed by list at the same indentation
+					Some(next,) if next.indent == indent && next.is_list_item() => {
+						parse_list(lines, pos, indent,)?
+					},
+					_ => Yaml::Scalar(String::new(),),
+				}
+			},
+			v => Yaml::Scalar(v.to_string(),),
+		};
+		entries.push((unquote(key,), value,),);
+	}
+
+	Ok(Yaml::Map(entries,),)
+}
+
+fn parse_list(lines: &[Line], pos: &mut usize, indent: usize,) -> Rslt<Yaml,> {
+	let mut items = vec![];
+	loop {
+		skip_blank(lines, pos,);
+		match lines.get(*pos,) {
+			Some(line,) if line.indent == indent && line.is_list_item() => {
+				*pos += 1;
+				let item = line.text[1..].trim();
+				if item.is_empty() {
+					skip_blank(lines, pos,);
+					match lines.get(*pos,) {
+						Some(next,) if next.indent > indent => {
+							items.push(parse_node(lines, pos, next.indent,)?,)
+						},
+						_ => items.push(Yaml::Scalar(String::new(),),),
+					}
+				} else {
+					items.push(Yaml::Scalar(item.to_string(),),);
+				}
+			},
+			_ => break,
+		}
+	}
+	Ok(Yaml::List(items,),)
+}
+
+/// lines indented deeper than the key. blank lines inside are kept, trailing ones dropped
+fn parse_block_scalar(lines: &[Line], pos: &mut usize, indent: usize,) -> Yaml {
+	let start = *pos;
+	while *pos < lines.len() && (lines[*pos].text.is_empty() || lines[*pos].indent > indent) {
+		*pos += 1;
+	}
+	while *pos > start && lines[*pos - 1].text.is_empty() {
+		*pos -= 1;
+	}
+
+	let block = &lines[start..*pos];
+	let min_indent =
+		block.iter().filter(|l| !l.text.is_empty(),).map(|l| l.indent,).min().unwrap_or(0,);
+	let text = block
+		.iter()
+		.map(|line| {
+			if line.text.is_empty() {
+				String::new()
+			} else {
+				" ".repeat(line.indent - min_indent,) + line.text
+			}
+		},)
+		.collect::<Vec<_,>>()
+		.join("\n",);
+	Yaml::Block(text,)
+}
+
+/// `key: value` -> `(key, value)`, `key:` -> `(key, "")`. keys may be quoted
+fn split_key(text: &str,) -> Option<(&str, &str,),> {
+	let key_end = match text.chars().next()? {
+		quote @ ('"' | '\'') => text[1..].find(quote,)? + 2,
+		_ => text.find(':',)?,
+	};
+	let rest = text[key_end..].strip_prefix(':',)?;
+	if !rest.is_empty() && !rest.starts_with(' ',) {
+		return None;
+	}
+	Some((&text[..key_end], rest.trim(),),)
+}
+
+/// items of a flow list, split on commas outside of quoted scalars
+fn split_flow_items(content: &str,) -> Vec<&str,> {
+	let mut items = vec![];
+	let mut start = 0;
+	let mut quote = None;
+	let mut chars = content.char_indices();
+	while let Some((i, c,),) = chars.next() {
+		match (quote, c,) {
+			(Some('"',), '\\',) => {
+				chars.next();
+			},
+			// `''` in a single quoted scalar closes and reopens it, which comes out the same
+			(Some(q,), c,) if c == q => quote = None,
+			(Some(_,), _,) => (),
+			(None, '"' | '\'',) => quote = Some(c,),
+			(None, ',',) => {
+				items.push(content[start..i].trim(),);
+				start = i + 1;
+			},
+			(None, _,) => (),
+		}
+	}
+	items.push(content[start..].trim(),);
+	items.retain(|item| !item.is_empty(),);
+	items
+}
+
+/// `"a\"b"` -> `a"b`, `'it''s'` -> `it's`, anything else as is
+pub fn unquote(scalar: &str,) -> String {
+	let scalar = scalar.trim();
+	if scalar.len() >= 2 && scalar.starts_with('"',) && scalar.ends_with('"',) {
+		serde_json::from_str(scalar,).unwrap_or_else(|_| scalar[1..scalar.len() - 1].to_string(),)
+	} else if scalar.len() >= 2 && scalar.starts_with('\'',) && scalar.ends_with('\'',) {
+		scalar[1..scalar.len() - 1].replace("''", "'",)
+	} else {
+		scalar.to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::description::DescriptionComposition;
+	use crate::raml::GenerationMode;
+	use crate::raml::create_raml_metadata_stream;
+	use crate::raml::render_raml;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_parse_hand_written_library() -> Rslt<(),> {
+		let content = r#"#%RAML 1.0 Library
+# hand written
+uses:
+  common: common.raml
+
+types:
+  Code: string
+  Kind:
+    type: string
+    description: kind of contract
+    enum: [a, "b, \"c\"", 'd, ''e''']
+  Note:
+    type: string
+    maxLength: 255
+    pattern: ^[a-z]+$
+    description: |
+      first line
+
+      second line
+    example: 'it''s'
+  Contract:
+    type: object
+    properties:
+      Code: Code
+      Kind?: Kind
+      Note:
+        type: Note
+        required: false
+"#;
+		let library = parse_raml_library(content,)?;
+		let names: Vec<_,> = library.types.iter().map(|(name, _,)| name.as_str(),).collect();
+		assert_eq!(names, ["Code", "Kind", "Note", "Contract"]);
+		let pattern = library.get("Note",).unwrap().get("pattern",);
+		assert_eq!(pattern, Some(&Yaml::Scalar("^[a-z]+$".to_string())));
+
+		let fields = library.field_types()?;
+		let variants: Vec<_,> = ["a", "b, \"c\"", "d, 'e'"].map(String::from,).to_vec();
+		assert_eq!(fields[1].type_on_raml, RamlType::Enum(variants, Box::new(RamlType::String)));
+		assert_eq!(fields[1].desc, "kind of contract");
+		assert_eq!(fields[2].desc, "first line\n\nsecond line");
+		assert_eq!(fields[2].max_length, Some(255));
+		assert_eq!(fields[2].example, "'it''s'");
+
+		let objects = library.object_types();
+		assert_eq!(objects, [RamlObjectType {
+			name:       "Contract".to_string(),
+			parent:     None,
+			properties: vec![
+				("Code".to_string(), "Code".to_string(), true),
+				("Kind".to_string(), "Kind".to_string(), false),
+				("Note".to_string(), "Note".to_string(), false),
+			],
+		}]);
+		Ok((),)
+	}
+
+	/// structural version of `raml::tests::test_raml_metadata_format`
+	#[test]
+	fn test_round_trip() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let rendered = render_raml(&raml_stream, GenerationMode::Crud,)?;
+		let parsed = parse_raml_library(&rendered,)?.to_metadata_stream()?;
+
+		assert_eq!(parsed.fields().len(), raml_stream.fields().len());
+		for (parsed, original,) in parsed.fields().iter().zip(raml_stream.fields(),) {
+			assert_eq!(parsed.name, original.name);
+			assert_eq!(parsed.type_on_raml, original.type_on_raml, "{}", original.name);
+			assert_eq!(parsed.desc, original.desc);
+			let description = DescriptionComposition::default().compose(original,);
+			assert_eq!(parsed.raml_description, Some(description,), "{}", original.name);
+			assert_eq!(parsed.example, original.example, "{}", original.name);
+			assert_eq!(parsed.default_value, original.default_value, "{}", original.name);
+			assert_eq!(parsed.required, original.required, "{}", original.name);
+			assert_eq!(parsed.read_only, original.read_only, "{}", original.name);
+		}
+
+		let objects = parse_raml_library(&rendered,)?.object_types();
+		let parents: Vec<_,> = objects.iter().map(|object| object.parent.as_deref(),).collect();
+		assert_eq!(parents, [None, Some("SampleUpdate"), Some("SampleCreate")]);
+		Ok((),)
+	}
+
+	#[test]
+	fn test_invalid_library() {
+		assert!(parse_raml_library("#%RAML 1.0\ntypes:\n",).is_err());
+		let bad_indent = "#%RAML 1.0 Library\ntypes:\n  A:\n    type: string\n      bad: 1\n";
+		assert!(parse_raml_library(bad_indent,).is_err());
+		let unsupported = parse_raml_library("#%RAML 1.0 Library\ntypes:\n  A: file\n",).unwrap();
+		assert!(unsupported.field_types().is_err());
+	}
+}
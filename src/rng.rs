@@ -0,0 +1,71 @@
+use std::ops::RangeInclusive;
+
+const ALPHANUMERIC: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789";
+
+/// splitmix64. the same seed gives the same sequence on every platform, so generated fixtures
+/// stay stable
+#[derive(Debug, Clone,)]
+pub struct Rng(u64,);
+
+impl Rng {
+	pub fn new(seed: u64,) -> Self {
+		Self(seed,)
+	}
+
+	pub fn next_u64(&mut self,) -> u64 {
+		self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15,);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9,);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB,);
+		z ^ (z >> 31)
+	}
+
+	/// uniform in `range`
+	pub fn range(&mut self, range: RangeInclusive<i64,>,) -> i64 {
+		let (start, end,) = range.into_inner();
+		let span = end.abs_diff(start,).wrapping_add(1,);
+		// the whole i64 range wraps to 0
+		let offset = if span == 0 { self.next_u64() } else { self.next_u64() % span };
+		start.wrapping_add_unsigned(offset,)
+	}
+
+	/// `true` with `probability` in `0.0..=1.0`
+	pub fn chance(&mut self, probability: f64,) -> bool {
+		((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+	}
+
+	pub fn pick<'a, T,>(&mut self, items: &'a [T],) -> Option<&'a T,> {
+		if items.is_empty() {
+			return None;
+		}
+		let i = self.range(0..=items.len() as i64 - 1,);
+		items.get(i as usize,)
+	}
+
+	pub fn alphanumeric(&mut self, len: usize,) -> String {
+		(0..len).map(|_| *self.pick(ALPHANUMERIC,).unwrap() as char,).collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn test_rng_is_deterministic() {
+		// reference output of splitmix64 seeded with 0
+		assert_eq!(Rng::new(0,).next_u64(), 0xE220_A839_7B1D_CDAF);
+
+		let (mut a, mut b,) = (Rng::new(42,), Rng::new(42,),);
+		let a: Vec<_,> = (0..8).map(|_| a.range(-3..=3,),).collect();
+		let b: Vec<_,> = (0..8).map(|_| b.range(-3..=3,),).collect();
+		assert_eq!(a, b);
+		assert!(a.iter().all(|n| (-3..=3).contains(n)));
+
+		let mut rng = Rng::new(7,);
+		assert_eq!(rng.pick::<u8>(&[],), None);
+		assert_eq!(rng.alphanumeric(18,).len(), 18);
+		assert!(!rng.chance(0.0));
+		assert!(rng.chance(1.0));
+	}
+}
@@ -0,0 +1,199 @@
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use crate::raml::type_base_name;
+use anyhow::Result as Rslt;
+use std::path::Path;
+
+const RUST_KEYWORDS: &[&str] = &[
+	"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+	"false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+	"ref", "return", "static", "struct", "super", "trait", "true", "type", "unsafe", "use",
+	"where", "while", "yield",
+];
+
+impl RamlMetadataStream {
+	pub fn create_rust_file(self, filename: String,) -> Rslt<(),> {
+		create_rust_file(self, format!("data/{filename}"),)
+	}
+}
+
+pub fn create_rust_file(data: RamlMetadataStream, filename: impl AsRef<Path,>,) -> Rslt<(),> {
+	let contents = render_rust(&data,)?;
+	std::fs::write(filename, contents,)?;
+	Ok((),)
+}
+
+/// serde struct named after the object plus one enum per picklist. depends on `serde` and
+/// `chrono` with its `serde` feature
+pub fn render_rust(data: &RamlMetadataStream,) -> Rslt<String,> {
+	let base = type_base_name(data.object_name(),)?;
+
+	let mut items = vec![format!(
+		"// generated by xraml from {}. do not edit\n\nuse serde::Deserialize;\nuse serde::Serialize;",
+		data.object_name()
+	)];
+
+	let mut fields = vec![];
+	for metadata in data.fields() {
+		let enum_name = format!("{base}{}", camel_case(&metadata.name,));
+		if let RamlType::Enum(variants, _,) = &metadata.type_on_raml
+			&& !variants.is_empty()
+		{
+			items.push(format_enum(&enum_name, metadata, variants,),);
+		}
+		fields.push(format_field(metadata, &enum_name,),);
+	}
+
+	items.push(format!(
+		"/// {}\n#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]\npub struct {base} \
+		 {{\n{}\n}}",
+		data.object_name(),
+		fields.join("\n",)
+	),);
+
+	let rslt = items.join("\n\n",) + "\n";
+	Ok(rslt,)
+}
+
+fn format_field(metadata: &RamlTypesMetadata, enum_name: &str,) -> String {
+	let rust_type = match &metadata.type_on_raml {
+		RamlType::Enum(variants, _,) if !variants.is_empty() => enum_name.to_string(),
+		raml_type => rust_type(raml_type,),
+	};
+
+	let mut lines = Vec::with_capacity(3,);
+	metadata.desc.lines().for_each(|line| lines.push(format!("/// {line}"),),);
+	if metadata.required {
+		lines.push(format!("#[serde(rename = \"{}\")]", metadata.name),);
+		lines.push(format!("pub {}: {rust_type},", field_ident(&metadata.name)),);
+	} else {
+		lines.push(format!(
+			"#[serde(rename = \"{}\", default, skip_serializing_if = \"Option::is_none\")]",
+			metadata.name
+		),);
+		lines.push(format!("pub {}: Option<{rust_type}>,", field_ident(&metadata.name)),);
+	}
+
+	let rslt = lines.join("\n\t",);
+	format!("\t{rslt}")
+}
+
+fn format_enum(enum_name: &str, metadata: &RamlTypesMetadata, variants: &[String],) -> String {
+	let mut lines = vec![
+		format!("/// {}", metadata.desc.lines().next().unwrap_or_default()),
+		"#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]".to_string(),
+		format!("pub enum {enum_name} {{"),
+	];
+
+	let mut used: Vec<String,> = vec![];
+	for (i, variant,) in variants.iter().enumerate() {
+		let mut ident = variant_ident(variant,).unwrap_or_else(|| format!("Variant{i}"),);
+		if used.contains(&ident,) {
+			ident = format!("{ident}{i}");
+		}
+		lines.push(format!("\t#[serde(rename = {variant:?})]"),);
+		lines.push(format!("\t{ident},"),);
+		used.push(ident,);
+	}
+	lines.push("}".to_string(),);
+
+	lines.join("\n",)
+}
+
+fn rust_type(raml_type: &RamlType,) -> String {
+	match raml_type {
+		RamlType::String => "String".to_string(),
+		RamlType::Number => "f64".to_string(),
+		RamlType::Enum(_, raml_type,) => rust_type(raml_type,),
+		RamlType::Boolean => "bool".to_string(),
+		RamlType::Date => "chrono::NaiveDate".to_string(),
+		RamlType::DateTime => "chrono::DateTime<chrono::Utc>".to_string(),
+		RamlType::Any => "serde_json::Value".to_string(),
+	}
+}
+
+/// `AccessCode__c` -> `AccessCode`, `ns__Foo__c` -> `NsFoo`
+fn camel_case(api_name: &str,) -> String {
+	api_name
+		.split("__",)
+		.filter(|part| !matches!(*part, "c" | "r" | ""),)
+		.flat_map(|part| part.split('_',),)
+		.map(|part| {
+			let mut chars = part.chars();
+			chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str(),).unwrap_or_default()
+		},)
+		.collect()
+}
+
+/// `AccessCode__c` -> `access_code`, `CreatedById` -> `created_by_id`
+fn field_ident(api_name: &str,) -> String {
+	let mut ident = String::with_capacity(api_name.len() + 4,);
+	let chars: Vec<char,> = camel_case(api_name,).chars().collect();
+	for (i, c,) in chars.iter().enumerate() {
+		let follows_lower =
+			i > 0 && (chars[i - 1].is_ascii_lowercase() || chars[i - 1].is_ascii_digit());
+		let precedes_lower = chars.get(i + 1,).is_some_and(|c| c.is_ascii_lowercase(),);
+		let starts_word = i > 0 && chars[i - 1].is_ascii_uppercase() && precedes_lower;
+		if c.is_ascii_uppercase() && (follows_lower || starts_word) {
+			ident.push('_',);
+		}
+		ident.push(c.to_ascii_lowercase(),);
+	}
+
+	if RUST_KEYWORDS.contains(&ident.as_str(),) { format!("r#{ident}") } else { ident }
+}
+
+/// ascii words of a picklist value in camel case. `89：一般` -> `V89`. `None` if the value has no
+/// ascii alphanumerics
+fn variant_ident(value: &str,) -> Option<String,> {
+	let ident: String = value
+		.split(|c: char| !c.is_ascii_alphanumeric(),)
+		.filter(|word| !word.is_empty(),)
+		.map(|word| {
+			let mut chars = word.chars();
+			let head = chars.next().unwrap().to_ascii_uppercase();
+			format!("{head}{}", chars.as_str())
+		},)
+		.collect();
+
+	match ident.chars().next() {
+		None => None,
+		Some(c,) if c.is_ascii_digit() => Some(format!("V{ident}"),),
+		Some(_,) => Some(ident,),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+	const GOLDEN: &str = include_str!("../tests/golden/sample.rs");
+
+	#[test]
+	fn test_idents() {
+		assert_eq!(camel_case("AccessCode__c"), "AccessCode");
+		assert_eq!(camel_case("ns__Foo_Bar__c"), "NsFooBar");
+		assert_eq!(field_ident("AccessCode__c"), "access_code");
+		assert_eq!(field_ident("CreatedById"), "created_by_id");
+		assert_eq!(field_ident("URLCode__c"), "url_code");
+		assert_eq!(field_ident("Type__c"), "r#type");
+		assert_eq!(variant_ident("89：一般"), Some("V89".to_string()));
+		assert_eq!(variant_ident("full time"), Some("FullTime".to_string()));
+		assert_eq!(variant_ident("正社員"), None);
+	}
+
+	/// `tests/rust_struct_golden.rs` compiles the golden file
+	#[test]
+	fn test_render_rust_matches_golden() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?.with_system_fields(&[
+			"Id",
+			"CreatedDate",
+		],)?;
+		let rendered = render_rust(&raml_stream,)?;
+		assert_eq!(rendered, GOLDEN);
+		Ok((),)
+	}
+}
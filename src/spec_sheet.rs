@@ -0,0 +1,126 @@
+use crate::csv::Csv;
+use crate::field_docs::length_text;
+use crate::raml::RamlMetadataStream;
+use anyhow::Result as Rslt;
+use std::path::Path;
+
+const SPEC_COLUMNS: [&str; 8] =
+	["No.", "項目名", "API参照名", "データ型", "桁数", "必須", "参照先", "説明"];
+
+/// marker column of a sheet written without an older one
+const DEFAULT_MARKER_COLUMN: &str = "CSV";
+
+impl RamlMetadataStream {
+	pub fn create_spec_csv_file(self, filename: String, previous: Option<&Csv,>,) -> Rslt<(),> {
+		create_spec_csv_file(&self, format!("data/{filename}"), previous,)
+	}
+}
+
+pub fn create_spec_csv_file(
+	data: &RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+	previous: Option<&Csv,>,
+) -> Rslt<(),> {
+	std::fs::write(filename, render_spec_csv(data, previous,),)?;
+	Ok((),)
+}
+
+/// `項目一覧` sheet of the object in the layout [`read_as_csv`](crate::csv::read_as_csv) reads.
+/// the marker columns and their 〇 of fields still on the object are taken from `previous`
+pub fn render_spec_csv(data: &RamlMetadataStream, previous: Option<&Csv,>,) -> String {
+	let marker_columns = match previous.map(Csv::marker_columns,) {
+		Some(columns,) if !columns.is_empty() => columns.to_vec(),
+		_ => vec![DEFAULT_MARKER_COLUMN.to_string()],
+	};
+	let markers = previous.map(Csv::markers,).unwrap_or_default();
+
+	let mut lines = vec![format!("項目一覧,{}", data.object_name())];
+	let header = SPEC_COLUMNS.iter().map(|column| column.to_string(),);
+	lines.push(row(header.chain(marker_columns.iter().cloned(),),),);
+
+	for (i, metadata,) in data.fields().iter().enumerate() {
+		let mut marker_cells = markers
+			.iter()
+			.find(|(name, _,)| *name == metadata.name,)
+			.map(|(_, cells,)| cells.clone(),)
+			.unwrap_or_default();
+		marker_cells.resize(marker_columns.len(), String::new(),);
+
+		let cells = [
+			(i + 1).to_string(),
+			metadata.desc.clone(),
+			metadata.name.clone(),
+			metadata.sf_type.clone(),
+			// `precision/scale` of numbers, as the reader splits on commas
+			length_text(metadata,).replace(", ", "/",),
+			if metadata.required { "〇" } else { "" }.to_string(),
+			metadata.reference_to.join("/",),
+			metadata.description.clone().unwrap_or_default(),
+		];
+		lines.push(row(cells.into_iter().chain(marker_cells,),),);
+	}
+	lines.join("\n",) + "\n"
+}
+
+/// api names of `previous` rows the object no longer has
+pub fn dropped_rows(data: &RamlMetadataStream, previous: &Csv,) -> Vec<String,> {
+	previous
+		.markers()
+		.into_iter()
+		.map(|(name, _,)| name,)
+		.filter(|name| !data.fields().iter().any(|metadata| metadata.name == *name,),)
+		.collect()
+}
+
+/// sheet rows start with an empty cell. the sheet reader splits on every comma and line, so
+/// commas become full-width and line breaks spaces
+fn row(cells: impl Iterator<Item = String,>,) -> String {
+	let cells = cells.map(|cell| cell.replace(',', "，",).replace(['\r', '\n',], " ",),);
+	std::iter::once(String::new(),).chain(cells,).collect::<Vec<_,>>().join(",",)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::csv::parse_as_csv;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_render_spec_csv() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let rendered = render_spec_csv(&raml_stream, None,);
+		let lines: Vec<_,> = rendered.lines().collect();
+		assert_eq!(lines[0], "項目一覧,Sample__c");
+		assert_eq!(lines[1], ",No.,項目名,API参照名,データ型,桁数,必須,参照先,説明,CSV");
+		assert_eq!(lines[3], ",2,36協定区分,Agreement__c,Picklist,,〇,,,");
+		assert_eq!(lines[4], ",3,取引先,Account__c,Lookup,18,,Account,,");
+		assert_eq!(lines[5], ",4,数量,Quantity__c,Number,4/0,〇,,,");
+		assert_eq!(lines.len(), 2 + raml_stream.fields().len());
+
+		let csv = parse_as_csv(&rendered,)?;
+		assert_eq!(csv.acquire_required_rows_name(), Vec::<String,>::new());
+		assert_eq!(csv.markers().len(), raml_stream.fields().len());
+		Ok((),)
+	}
+
+	#[test]
+	fn test_merge_markers() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let previous = parse_as_csv(
+			"個別契約\n項目一覧\n,No.,項目名,API参照名,CSV(IC),備考\n,1,数量,Quantity__c,〇,必須\n\
+			 ,2,旧項目,Old__c,〇,\n,3,単価,UnitPrice__c,,税抜\n",
+		)?;
+		assert_eq!(previous.acquire_required_rows_name(), ["Quantity__c", "Old__c"]);
+		assert_eq!(dropped_rows(&raml_stream, &previous,), ["Old__c"]);
+
+		let rendered = render_spec_csv(&raml_stream, Some(&previous,),);
+		assert!(rendered.lines().nth(1).unwrap().ends_with(",説明,CSV(IC),備考"));
+		let csv = parse_as_csv(&rendered,)?;
+		assert_eq!(csv.acquire_required_rows_name(), ["Quantity__c"]);
+		let unit_price = csv.markers().into_iter().find(|(name, _,)| name == "UnitPrice__c",);
+		assert_eq!(unit_price.unwrap().1, ["", "税抜"]);
+		Ok((),)
+	}
+}
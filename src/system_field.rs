@@ -0,0 +1,144 @@
+use crate::raml::RamlTypesMetadata;
+use crate::raml::sf_type_to_raml;
+
+/// standard field every custom object has, though `.object` files never list them
+#[derive(Debug, Clone, Copy, PartialEq, Eq,)]
+pub struct SystemField {
+	pub name:         &'static str,
+	pub label:        &'static str,
+	/// salesforce field type
+	pub sf_type:      &'static str,
+	pub length:       Option<usize,>,
+	/// objects the lookup points to
+	pub reference_to: &'static [&'static str],
+	pub required:     bool,
+	pub read_only:    bool,
+}
+
+pub const SYSTEM_FIELDS: &[SystemField] = &[
+	SystemField {
+		name:         "Id",
+		label:        "レコードID",
+		sf_type:      "Id",
+		length:       None,
+		reference_to: &[],
+		required:     true,
+		read_only:    true,
+	},
+	SystemField {
+		name:         "Name",
+		label:        "名前",
+		sf_type:      "Text",
+		length:       Some(80,),
+		reference_to: &[],
+		// auto-number names are filled in by salesforce
+		required:     false,
+		read_only:    false,
+	},
+	SystemField {
+		name:         "OwnerId",
+		label:        "所有者ID",
+		sf_type:      "Lookup",
+		length:       None,
+		reference_to: &["User", "Group"],
+		required:     false,
+		read_only:    false,
+	},
+	SystemField {
+		name:         "RecordTypeId",
+		label:        "レコードタイプID",
+		sf_type:      "Lookup",
+		length:       None,
+		reference_to: &["RecordType"],
+		required:     false,
+		read_only:    false,
+	},
+	SystemField {
+		name:         "CreatedDate",
+		label:        "作成日",
+		sf_type:      "DateTime",
+		length:       None,
+		reference_to: &[],
+		required:     true,
+		read_only:    true,
+	},
+	SystemField {
+		name:         "CreatedById",
+		label:        "作成者ID",
+		sf_type:      "Lookup",
+		length:       None,
+		reference_to: &["User"],
+		required:     true,
+		read_only:    true,
+	},
+	SystemField {
+		name:         "LastModifiedDate",
+		label:        "最終更新日",
+		sf_type:      "DateTime",
+		length:       None,
+		reference_to: &[],
+		required:     true,
+		read_only:    true,
+	},
+	SystemField {
+		name:         "LastModifiedById",
+		label:        "最終更新者ID",
+		sf_type:      "Lookup",
+		length:       None,
+		reference_to: &["User"],
+		required:     true,
+		read_only:    true,
+	},
+	SystemField {
+		name:         "SystemModstamp",
+		label:        "System Modstamp",
+		sf_type:      "DateTime",
+		length:       None,
+		reference_to: &[],
+		required:     true,
+		read_only:    true,
+	},
+	SystemField {
+		name:         "IsDeleted",
+		label:        "削除済み",
+		sf_type:      "Checkbox",
+		length:       None,
+		reference_to: &[],
+		required:     true,
+		read_only:    true,
+	},
+];
+
+impl SystemField {
+	pub fn find(name: impl AsRef<str,>,) -> Option<&'static Self,> {
+		SYSTEM_FIELDS.iter().find(|field| field.name == name.as_ref(),)
+	}
+
+	pub fn all_names() -> Vec<String,> {
+		SYSTEM_FIELDS.iter().map(|field| field.name.to_string(),).collect()
+	}
+
+	pub fn to_raml_metadata(&self,) -> RamlTypesMetadata {
+		let mut max_length = self.length;
+		let (type_on_raml, example,) = sf_type_to_raml(self.sf_type, &mut max_length,);
+
+		RamlTypesMetadata {
+			name: self.name.to_string(),
+			sf_type: self.sf_type.to_string(),
+			type_on_raml,
+			desc: self.label.to_string(),
+			description: None,
+			help_text: None,
+			translations: vec![],
+			raml_description: None,
+			example,
+			default_value: None,
+			max_length,
+			precision: None,
+			scale: None,
+			reference_to: self.reference_to.iter().map(|name| name.to_string(),).collect(),
+			required: self.required,
+			read_only: self.read_only,
+		}
+	}
+}
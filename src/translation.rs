@@ -0,0 +1,239 @@
+use crate::raml::RamlMetadataStream;
+use crate::read_file;
+use anyhow::Result as Rslt;
+use anyhow::anyhow;
+use anyhow::bail;
+use roxmltree::Document;
+use roxmltree::Node;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// directory salesforce keeps translations in, next to `objects`
+const TRANSLATIONS_DIR: &str = "objectTranslations";
+
+/// label and help text of a field in another language
+#[derive(Debug, Clone, PartialEq, Eq,)]
+pub struct FieldTranslation {
+	/// locale as salesforce names it, e.g. `en_US`
+	pub language:  String,
+	/// `None` when the label is not translated
+	pub label:     Option<String,>,
+	pub help_text: Option<String,>,
+}
+
+impl RamlMetadataStream {
+	/// attaches translations of every language in `languages`, read from the
+	/// `objectTranslations` directory of the project `object_path` belongs to
+	pub fn load_translations(
+		self,
+		object_path: impl AsRef<Path,>,
+		languages: &[String],
+	) -> Rslt<Self,> {
+		if languages.is_empty() {
+			return Ok(self,);
+		}
+
+		let object_path = object_path.as_ref();
+		let dir = translations_dir(object_path,).ok_or(anyhow!(
+			"no `{TRANSLATIONS_DIR}` directory found for {}",
+			object_path.display()
+		),)?;
+		let object_name = self.object_name().to_string();
+		languages.iter().try_fold(self, |stream, language| {
+			let translations = read_object_translations(&dir, &object_name, language,)?;
+			Ok(stream.with_translations(&translations,),)
+		},)
+	}
+
+	/// `translations` are `(field name, translation)` pairs. a field keeps one translation per
+	/// language, the last one given
+	pub fn with_translations(mut self, translations: &[(String, FieldTranslation,)],) -> Self {
+		for (name, translation,) in translations {
+			let Some(metadata,) = self.fields_mut().iter_mut().find(|field| field.name == *name,)
+			else {
+				continue;
+			};
+			metadata.translations.retain(|attached| attached.language != translation.language,);
+			metadata.translations.push(translation.clone(),);
+		}
+		self
+	}
+
+	/// the stream as consumers of `language` read it. untranslated labels and help texts stay
+	/// as they are, like salesforce falls back to the default language. descriptions composed
+	/// before are dropped, compose them again
+	pub fn translated(mut self, language: &str,) -> Self {
+		for metadata in self.fields_mut() {
+			metadata.raml_description = None;
+			let Some(translation,) =
+				metadata.translations.iter().find(|translation| translation.language == language,)
+			else {
+				continue;
+			};
+			if let Some(label,) = &translation.label {
+				metadata.desc = label.clone();
+			}
+			if let Some(help_text,) = &translation.help_text {
+				metadata.help_text = Some(help_text.clone(),);
+			}
+		}
+		self
+	}
+}
+
+/// `individual_contract.raml` becomes `individual_contract-en_US.raml`
+pub fn translated_filename(filename: &str, language: &str,) -> String {
+	match filename.rsplit_once('.',) {
+		Some((stem, extension,),) => format!("{stem}-{language}.{extension}"),
+		None => format!("{filename}-{language}"),
+	}
+}
+
+/// nearest `objectTranslations` directory among the ancestors of `object_path`. covers both
+/// `objects/Foo__c.object` and the sfdx `objects/Foo__c/Foo__c.object-meta.xml`
+pub fn translations_dir(object_path: impl AsRef<Path,>,) -> Option<PathBuf,> {
+	object_path
+		.as_ref()
+		.ancestors()
+		.skip(1,)
+		.map(|dir| dir.join(TRANSLATIONS_DIR,),)
+		.find(|dir| dir.is_dir(),)
+}
+
+/// field translations of `object_name` in `language`, from `Foo__c-en_US.objectTranslation` or
+/// the sfdx `Foo__c-en_US/*.fieldTranslation-meta.xml` files
+pub fn read_object_translations(
+	dir: impl AsRef<Path,>,
+	object_name: &str,
+	language: &str,
+) -> Rslt<Vec<(String, FieldTranslation,),>,> {
+	let dir = dir.as_ref();
+	let base_name = format!("{object_name}-{language}");
+
+	let metadata_api_file = dir.join(format!("{base_name}.objectTranslation"),);
+	if metadata_api_file.is_file() {
+		return parse_translation(&read_file(metadata_api_file,)?, language,);
+	}
+
+	let sfdx_dir = dir.join(&base_name,);
+	if !sfdx_dir.is_dir() {
+		bail!("no translation of {object_name} into {language} in {}", dir.display())
+	}
+	let mut paths: Vec<PathBuf,> = std::fs::read_dir(sfdx_dir,)?
+		.map(|entry| Ok(entry?.path(),),)
+		.collect::<Rslt<_,>>()?;
+	paths.retain(|path| path.to_string_lossy().ends_with(".fieldTranslation-meta.xml",),);
+	paths.sort();
+
+	let mut translations = vec![];
+	for path in paths {
+		translations.extend(parse_translation(&read_file(path,)?, language,)?,);
+	}
+	Ok(translations,)
+}
+
+/// fields of a `CustomObjectTranslation`, or the field a `CustomFieldTranslation` is about.
+/// fields with neither label nor help text translated are left out
+pub fn parse_translation(
+	content: &str,
+	language: &str,
+) -> Rslt<Vec<(String, FieldTranslation,),>,> {
+	let doc = Document::parse(content,)?;
+	let root = doc.root_element();
+	let fields: Vec<Node,> = match root.tag_name().name() {
+		"CustomObjectTranslation" => {
+			root.children().filter(|child| child.tag_name().name() == "fields",).collect()
+		},
+		"CustomFieldTranslation" => vec![root],
+		other => bail!("expect CustomObjectTranslation or CustomFieldTranslation, found `{other}`"),
+	};
+
+	let mut translations = vec![];
+	for field in fields {
+		// untranslated entries come as `<label><!-- Label --></label>`
+		let text = |tag: &str| {
+			let node = field.children().find(|child| child.tag_name().name() == tag,)?;
+			node.text().map(str::trim,).filter(|text| !text.is_empty(),).map(String::from,)
+		};
+		let name = text("name",).ok_or(anyhow!("field translation without `<name>`"),)?;
+		let translation = FieldTranslation {
+			language:  language.to_string(),
+			label:     text("label",),
+			help_text: text("help",),
+		};
+		if translation.label.is_some() || translation.help_text.is_some() {
+			translations.push((name, translation,),);
+		}
+	}
+	Ok(translations,)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::field_docs::DocsFormat;
+	use crate::field_docs::render_field_docs;
+	use crate::raml::create_raml_metadata_stream;
+	use crate::raml::render_raml;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+	const CONTRACT_PATH: &str = "tests/fixtures/Contract__c.object";
+
+	fn labels(stream: &RamlMetadataStream,) -> Vec<&str,> {
+		stream.fields().iter().map(|metadata| metadata.desc.as_str(),).collect()
+	}
+
+	#[test]
+	fn test_read_object_translations() -> Rslt<(),> {
+		let dir = translations_dir(SAMPLE_PATH,).unwrap();
+		assert_eq!(dir, Path::new("tests/fixtures/objectTranslations"));
+
+		let translations = read_object_translations(&dir, "Sample__c", "en_US",)?;
+		let names: Vec<_,> = translations.iter().map(|(name, _,)| name.as_str(),).collect();
+		assert_eq!(names, ["AccessCode__c", "Agreement__c"]);
+		assert_eq!(translations[0].1, FieldTranslation {
+			language:  "en_US".to_string(),
+			label:     Some("E-Contract Access Code".to_string()),
+			help_text: Some("18 alphanumeric characters in the URL of the contract".to_string()),
+		});
+		assert_eq!(translations[1].1.help_text, None);
+
+		let translations = read_object_translations(&dir, "Contract__c", "en_US",)?;
+		let names: Vec<_,> = translations.iter().map(|(name, _,)| name.as_str(),).collect();
+		assert_eq!(names, ["Amount__c", "Signer__c"]);
+		assert_eq!(translations[0].1.help_text.as_deref(), Some("Excluding tax"));
+
+		assert!(read_object_translations(&dir, "Sample__c", "zh_CN",).is_err());
+		assert!(parse_translation("<CustomObject/>", "en_US",).is_err());
+		Ok((),)
+	}
+
+	#[test]
+	fn test_translated_stream() -> Rslt<(),> {
+		let languages = vec!["en_US".to_string()];
+		let sample = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let sample = sample.load_translations(SAMPLE_PATH, &languages,)?;
+		assert_eq!(sample.fields()[0].translations.len(), 1);
+
+		let translated = sample.clone().translated("en_US",);
+		assert_eq!(labels(&translated,)[..3], [
+			"E-Contract Access Code",
+			"Article 36 Agreement",
+			"取引先"
+		]);
+		assert_eq!(labels(&sample.clone().translated("fr",),), labels(&sample,));
+
+		let rendered = render_raml(&translated, Default::default(),)?;
+		let expected = "    displayName: \"E-Contract Access Code\"\n    description: |\n      \
+		                電子契約サービスから払い出される | 区切りなしの照会コード\n\n      18 \
+		                alphanumeric characters in the URL of the contract\n";
+		assert!(rendered.contains(expected), "{rendered}");
+
+		let contract = create_raml_metadata_stream(CONTRACT_PATH,)?;
+		let contract = contract.load_translations(CONTRACT_PATH, &languages,)?.translated("en_US",);
+		let markdown = render_field_docs(&contract, DocsFormat::Markdown,);
+		assert!(markdown.contains("| Amount__c | Amount |  | Number |"), "{markdown}");
+		assert_eq!(translated_filename("contract.raml", "en_US",), "contract-en_US.raml");
+		Ok((),)
+	}
+}
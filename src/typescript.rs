@@ -0,0 +1,133 @@
+use crate::description::DescriptionComposition;
+use crate::raml::RamlMetadataStream;
+use crate::raml::RamlType;
+use crate::raml::RamlTypesMetadata;
+use crate::raml::type_base_name;
+use anyhow::Result as Rslt;
+use serde_json::Value;
+use std::path::Path;
+
+impl RamlMetadataStream {
+	pub fn create_typescript_file(self, filename: String,) -> Rslt<(),> {
+		create_typescript_file(self, format!("data/{filename}"),)
+	}
+}
+
+pub fn create_typescript_file(
+	data: RamlMetadataStream,
+	filename: impl AsRef<Path,>,
+) -> Rslt<(),> {
+	let contents = render_typescript(&data,)?;
+	std::fs::write(filename, contents,)?;
+	Ok((),)
+}
+
+/// one `export interface` named after the object, e.g. `Sample` for `Sample__c`
+pub fn render_typescript(data: &RamlMetadataStream,) -> Rslt<String,> {
+	let base = type_base_name(data.object_name(),)?;
+
+	let mut lines = vec![format!("export interface {base} {{")];
+	data.fields().iter().for_each(|metadata| lines.push(format_as_typescript(metadata,),),);
+	lines.push("}".to_string(),);
+
+	let rslt = lines.join("\n",) + "\n";
+	Ok(rslt,)
+}
+
+/// jsdoc and property declaration. optional unless the field is required
+pub fn format_as_typescript(metadata: &RamlTypesMetadata,) -> String {
+	let mut lines = Vec::with_capacity(4,);
+	lines.push("/**".to_string(),);
+	metadata.desc.lines().for_each(|line| lines.push(jsdoc_line(line,),),);
+	// the same description as the raml type. it is the label when there is nothing else
+	let description = metadata
+		.raml_description
+		.clone()
+		.unwrap_or_else(|| DescriptionComposition::default().compose(metadata,),);
+	if description.trim() != metadata.desc.trim() {
+		lines.push(" *".to_string(),);
+		description.lines().for_each(|line| lines.push(jsdoc_line(line,),),);
+	}
+	if let Some(max_length,) = metadata.max_length {
+		lines.push(format!(" * max length: {max_length}"),);
+	}
+	lines.push(" */".to_string(),);
+
+	let readonly = if metadata.read_only { "readonly " } else { "" };
+	let optional = if metadata.required { "" } else { "?" };
+	let ts_type = ts_type(&metadata.type_on_raml,);
+	lines.push(format!("{readonly}{}{optional}: {ts_type};", metadata.name),);
+
+	let rslt = lines.join("\n\t",);
+	format!("\t{rslt}")
+}
+
+/// `*/` in the text would end the comment
+fn jsdoc_line(line: &str,) -> String {
+	if line.trim().is_empty() {
+		" *".to_string()
+	} else {
+		format!(" * {}", line.replace("*/", "*\\/",))
+	}
+}
+
+fn ts_type(raml_type: &RamlType,) -> String {
+	match raml_type {
+		RamlType::String => "string".to_string(),
+		RamlType::Number => "number".to_string(),
+		RamlType::Enum(items, raml_type,) if items.is_empty() => ts_type(raml_type,),
+		// string literal union. json string literals are valid in typescript
+		RamlType::Enum(items, _,) => items
+			.iter()
+			.map(|item| Value::from(item.as_str(),).to_string(),)
+			.collect::<Vec<_,>>()
+			.join(" | ",),
+		RamlType::Boolean => "boolean".to_string(),
+		// iso 8601 strings on the wire
+		RamlType::Date | RamlType::DateTime => "string".to_string(),
+		RamlType::Any => "unknown".to_string(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::raml::create_raml_metadata_stream;
+
+	const SAMPLE_PATH: &str = "tests/fixtures/Sample__c.object";
+
+	#[test]
+	fn test_render_typescript() -> Rslt<(),> {
+		let raml_stream = create_raml_metadata_stream(SAMPLE_PATH,)?;
+		let rendered = render_typescript(&raml_stream,)?;
+
+		let answer = r#"export interface Sample {
+	/**
+	 * 電子契約-アクセスコード
+	 *
+	 * 電子契約サービスから払い出される | 区切りなしの照会コード
+	 *
+	 * 契約書の URL に含まれる 18 桁の英数字
+	 * max length: 18
+	 */
+	AccessCode__c?: string;
+	/**
+	 * 36協定区分
+	 */
+	Agreement__c: "89：一般" | "90：一般（フレックス）" | "93：適用除外業務";
+"#;
+		assert!(rendered.starts_with(answer), "{rendered}");
+		assert!(rendered.contains("\tIsSigned__c?: boolean;\n"));
+		assert!(rendered.contains("\tStartDate__c?: string;\n"));
+		assert!(rendered.contains("\treadonly TotalAmount__c?: number;\n"));
+		assert!(rendered.ends_with("\treadonly ContractNo__c?: string;\n}\n"));
+
+		let mut metadata = raml_stream.fields()[1].clone();
+		metadata.desc = "区分 */ 旧".to_string();
+		metadata.raml_description = Some("/* 89 */ 一般".to_string(),);
+		let formatted = format_as_typescript(&metadata,);
+		assert!(formatted.starts_with("\t/**\n\t * 区分 *\\/ 旧\n\t *\n\t * /* 89 *\\/ 一般\n\t */\n"));
+		assert_eq!(formatted.matches("*/",).count(), 1);
+		Ok((),)
+	}
+}
@@ -0,0 +1,56 @@
+// generated by xraml from Sample__c. do not edit
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// 36協定区分
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SampleAgreement {
+	#[serde(rename = "89：一般")]
+	V89,
+	#[serde(rename = "90：一般（フレックス）")]
+	V90,
+	#[serde(rename = "93：適用除外業務")]
+	V93,
+}
+
+/// Sample__c
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+	/// レコードID
+	#[serde(rename = "Id")]
+	pub id: String,
+	/// 作成日
+	#[serde(rename = "CreatedDate")]
+	pub created_date: chrono::DateTime<chrono::Utc>,
+	/// 電子契約-アクセスコード
+	#[serde(rename = "AccessCode__c", default, skip_serializing_if = "Option::is_none")]
+	pub access_code: Option<String>,
+	/// 36協定区分
+	#[serde(rename = "Agreement__c")]
+	pub agreement: SampleAgreement,
+	/// 取引先
+	#[serde(rename = "Account__c", default, skip_serializing_if = "Option::is_none")]
+	pub account: Option<String>,
+	/// 数量
+	#[serde(rename = "Quantity__c")]
+	pub quantity: f64,
+	/// 単価
+	#[serde(rename = "UnitPrice__c", default, skip_serializing_if = "Option::is_none")]
+	pub unit_price: Option<f64>,
+	/// 締結済
+	#[serde(rename = "IsSigned__c", default, skip_serializing_if = "Option::is_none")]
+	pub is_signed: Option<bool>,
+	/// 開始日
+	#[serde(rename = "StartDate__c", default, skip_serializing_if = "Option::is_none")]
+	pub start_date: Option<chrono::NaiveDate>,
+	/// 合計金額
+	#[serde(rename = "TotalAmount__c", default, skip_serializing_if = "Option::is_none")]
+	pub total_amount: Option<f64>,
+	/// 契約数
+	#[serde(rename = "ContractCount__c", default, skip_serializing_if = "Option::is_none")]
+	pub contract_count: Option<f64>,
+	/// 契約番号
+	#[serde(rename = "ContractNo__c", default, skip_serializing_if = "Option::is_none")]
+	pub contract_no: Option<String>,
+}
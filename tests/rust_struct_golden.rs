@@ -0,0 +1,29 @@
+//! the rust code generated from `tests/fixtures/Sample__c.object` has to compile and round-trip
+//! salesforce json
+
+#[path = "golden/sample.rs"]
+mod sample;
+
+use sample::Sample;
+use sample::SampleAgreement;
+
+#[test]
+fn test_golden_round_trip() -> anyhow::Result<(),> {
+	let body = r#"{
+		"Id": "a0B000000000001AAA",
+		"CreatedDate": "2024-04-01T09:00:00Z",
+		"Agreement__c": "89：一般",
+		"Quantity__c": 3,
+		"StartDate__c": "2024-04-01"
+	}"#;
+
+	let sample: Sample = serde_json::from_str(body,)?;
+	assert_eq!(sample.agreement, SampleAgreement::V89);
+	assert_eq!(sample.start_date, Some(chrono::NaiveDate::from_ymd_opt(2024, 4, 1,).unwrap()));
+	assert_eq!(sample.access_code, None);
+
+	let json = serde_json::to_value(&sample,)?;
+	assert_eq!(json["Agreement__c"], "89：一般");
+	assert!(json.get("AccessCode__c").is_none());
+	Ok((),)
+}